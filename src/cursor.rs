@@ -0,0 +1,348 @@
+use crate::SlabLinkedList;
+
+#[derive(Debug)]
+pub struct Cursor<'a, T> {
+    list: &'a SlabLinkedList<T>,
+    current: Option<usize>,
+}
+
+impl<T> Clone for Cursor<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Cursor<'_, T> {}
+
+impl<'a, T> Cursor<'a, T> {
+    #[inline]
+    pub(crate) fn new(list: &'a SlabLinkedList<T>, current: Option<usize>) -> Self {
+        Self { list, current }
+    }
+
+    #[inline]
+    pub fn key(&self) -> Option<usize> {
+        self.current
+    }
+
+    #[inline]
+    pub fn current(&self) -> Option<&'a T> {
+        self.list.get(self.current?)
+    }
+
+    #[inline]
+    pub fn move_next(&mut self) {
+        self.current = match self.current {
+            None => self.list.head,
+            Some(key) => self.list.next_key(key),
+        };
+    }
+
+    #[inline]
+    pub fn move_prev(&mut self) {
+        self.current = match self.current {
+            None => self.list.tail,
+            Some(key) => self.list.prev_key(key),
+        };
+    }
+
+    #[inline]
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let key = match self.current {
+            None => self.list.head,
+            Some(key) => self.list.next_key(key),
+        };
+        self.list.get(key?)
+    }
+
+    #[inline]
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let key = match self.current {
+            None => self.list.tail,
+            Some(key) => self.list.prev_key(key),
+        };
+        self.list.get(key?)
+    }
+
+    #[inline]
+    pub fn as_list(&self) -> &'a SlabLinkedList<T> {
+        self.list
+    }
+}
+
+#[derive(Debug)]
+pub struct CursorMut<'a, T> {
+    list: &'a mut SlabLinkedList<T>,
+    current: Option<usize>,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    #[inline]
+    pub(crate) fn new(list: &'a mut SlabLinkedList<T>, current: Option<usize>) -> Self {
+        Self { list, current }
+    }
+
+    #[inline]
+    pub fn key(&self) -> Option<usize> {
+        self.current
+    }
+
+    #[inline]
+    pub fn current(&self) -> Option<&T> {
+        self.list.get(self.current?)
+    }
+
+    #[inline]
+    pub fn current_mut(&mut self) -> Option<&mut T> {
+        self.list.get_mut(self.current?)
+    }
+
+    #[inline]
+    pub fn move_next(&mut self) {
+        self.current = match self.current {
+            None => self.list.head,
+            Some(key) => self.list.next_key(key),
+        };
+    }
+
+    #[inline]
+    pub fn move_prev(&mut self) {
+        self.current = match self.current {
+            None => self.list.tail,
+            Some(key) => self.list.prev_key(key),
+        };
+    }
+
+    #[inline]
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let key = match self.current {
+            None => self.list.head,
+            Some(key) => self.list.next_key(key),
+        };
+        self.list.get_mut(key?)
+    }
+
+    #[inline]
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let key = match self.current {
+            None => self.list.tail,
+            Some(key) => self.list.prev_key(key),
+        };
+        self.list.get_mut(key?)
+    }
+
+    #[inline]
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor::new(self.list, self.current)
+    }
+
+    #[inline]
+    pub fn as_list(&self) -> &SlabLinkedList<T> {
+        self.list
+    }
+
+    /// Inserts before the current element, or at the back if the cursor is on the ghost position.
+    #[inline]
+    pub fn insert_before(&mut self, value: T) -> usize {
+        match self.current {
+            None => self.list.push_back(value),
+            Some(key) => self.list.insert_before(value, key),
+        }
+    }
+
+    /// Inserts after the current element, or at the front if the cursor is on the ghost position.
+    #[inline]
+    pub fn insert_after(&mut self, value: T) -> usize {
+        match self.current {
+            None => self.list.push_front(value),
+            Some(key) => self.list.insert_after(value, key),
+        }
+    }
+
+    /// Removes the current element and moves the cursor to the next one.
+    #[inline]
+    pub fn remove_current(&mut self) -> Option<T> {
+        let key = self.current?;
+        self.current = self.list.next_key(key);
+        Some(self.list.remove(key))
+    }
+
+    /// Moves every element after the current one into a new list. Returns `(old_key, new_key)`
+    /// pairs along with it.
+    ///
+    /// On the ghost position the whole list is moved and keeps its keys.
+    /// Otherwise the moved elements are assigned new keys.
+    pub fn split_after(&mut self) -> (SlabLinkedList<T>, Vec<(usize, usize)>) {
+        match self.current {
+            None => {
+                let list = std::mem::take(self.list);
+                let remap = list.keys().map(|key| (key, key)).collect();
+                (list, remap)
+            }
+            Some(key) => self.list.split_off_after(key),
+        }
+    }
+
+    /// Moves every element before the current one into a new list. Returns `(old_key, new_key)`
+    /// pairs along with it.
+    ///
+    /// On the ghost position the whole list is moved and keeps its keys.
+    /// Otherwise the moved elements are assigned new keys.
+    pub fn split_before(&mut self) -> (SlabLinkedList<T>, Vec<(usize, usize)>) {
+        match self.current {
+            None => {
+                let list = std::mem::take(self.list);
+                let remap = list.keys().map(|key| (key, key)).collect();
+                (list, remap)
+            }
+            Some(key) => self.list.split_off_before(key),
+        }
+    }
+
+    /// Moves every element of `other` in after the current element, or to the front
+    /// if the cursor is on the ghost position. Returns `(old_key, new_key)` pairs.
+    pub fn splice_after(&mut self, mut other: SlabLinkedList<T>) -> Vec<(usize, usize)> {
+        match self.current {
+            None => self.list.prepend(&mut other),
+            Some(key) => self.list.splice_after(key, &mut other),
+        }
+    }
+
+    /// Moves every element of `other` in before the current element, or to the back
+    /// if the cursor is on the ghost position. Returns `(old_key, new_key)` pairs.
+    pub fn splice_before(&mut self, mut other: SlabLinkedList<T>) -> Vec<(usize, usize)> {
+        match self.current {
+            None => self.list.append(&mut other),
+            Some(key) => self.list.splice_before(key, &mut other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect<T: Copy>(list: &SlabLinkedList<T>) -> Vec<T> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn cursor() {
+        let mut list = SlabLinkedList::new();
+        let i1 = list.push_back(1);
+        let i2 = list.push_back(2);
+        let i3 = list.push_back(3);
+
+        let mut cursor = list.cursor_at(i2).unwrap();
+        assert_eq!(cursor.key(), Some(i2));
+        assert_eq!(cursor.current(), Some(&2));
+        assert_eq!(cursor.peek_next(), Some(&3));
+        assert_eq!(cursor.peek_prev(), Some(&1));
+        cursor.move_next();
+        assert_eq!(cursor.key(), Some(i3));
+        cursor.move_next();
+        assert_eq!(cursor.key(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&1));
+        assert_eq!(cursor.peek_prev(), Some(&3));
+        cursor.move_next();
+        assert_eq!(cursor.key(), Some(i1));
+        cursor.move_prev();
+        assert_eq!(cursor.key(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.key(), Some(i3));
+
+        assert_eq!(list.cursor_front().key(), Some(i1));
+        assert_eq!(list.cursor_back().key(), Some(i3));
+        list.remove(i2);
+        assert!(list.cursor_at(i2).is_none());
+    }
+
+    #[test]
+    fn cursor_mut() {
+        let mut list = SlabLinkedList::new();
+        let i1 = list.push_back(1);
+        let i3 = list.push_back(3);
+
+        let mut cursor = list.cursor_at_mut(i1).unwrap();
+        let i2 = cursor.insert_after(2);
+        let i0 = cursor.insert_before(0);
+        *cursor.current_mut().unwrap() += 10;
+        *cursor.peek_next().unwrap() += 10;
+        cursor.move_prev();
+        cursor.move_prev();
+        let i4 = cursor.insert_before(4);
+        let i5 = cursor.insert_after(5);
+        assert_eq!(collect(&list), [5, 0, 11, 12, 3, 4]);
+        assert_eq!(list.keys().collect::<Vec<_>>(), [i5, i0, i1, i2, i3, i4]);
+
+        let mut cursor = list.cursor_at_mut(i2).unwrap();
+        assert_eq!(cursor.remove_current(), Some(12));
+        assert_eq!(cursor.key(), Some(i3));
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(4));
+        assert_eq!(cursor.key(), None);
+        assert_eq!(cursor.remove_current(), None);
+        assert_eq!(collect(&list), [5, 0, 11, 3]);
+    }
+
+    #[test]
+    fn split() {
+        let mut list = SlabLinkedList::new();
+        let keys = (0..6).map(|v| list.push_back(v)).collect::<Vec<_>>();
+
+        let mut cursor = list.cursor_at_mut(keys[3]).unwrap();
+        let (after, after_remap) = cursor.split_after();
+        let (before, before_remap) = cursor.split_before();
+        assert_eq!(collect(&before), [0, 1, 2]);
+        assert_eq!(collect(&after), [4, 5]);
+        assert_eq!(collect(&list), [3]);
+        assert_eq!(list.front_key(), Some(keys[3]));
+        for (i, (old_key, new_key)) in before_remap.into_iter().enumerate() {
+            assert_eq!(old_key, keys[i]);
+            assert_eq!(before[new_key], i);
+        }
+        for (i, (old_key, new_key)) in after_remap.into_iter().enumerate() {
+            assert_eq!(old_key, keys[i + 4]);
+            assert_eq!(after[new_key], i + 4);
+        }
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        let (all, remap) = cursor.split_after();
+        assert!(list.is_empty());
+        assert_eq!(all.front_key(), Some(keys[3]));
+        assert_eq!(remap, [(keys[3], keys[3])]);
+    }
+
+    #[test]
+    fn splice() {
+        let list_of = |values: &[i32]| {
+            let mut list = SlabLinkedList::new();
+            for value in values {
+                list.push_back(*value);
+            }
+            list
+        };
+
+        let mut list = list_of(&[1, 5]);
+        let i1 = list.front_key().unwrap();
+        let mut cursor = list.cursor_at_mut(i1).unwrap();
+        cursor.splice_after(list_of(&[2, 3, 4]));
+        cursor.splice_before(list_of(&[-1, 0]));
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.key(), None);
+        cursor.splice_after(list_of(&[-3, -2]));
+        cursor.splice_before(list_of(&[6, 7]));
+        assert_eq!(collect(&list), [-3, -2, -1, 0, 1, 2, 3, 4, 5, 6, 7]);
+
+        let mut empty = SlabLinkedList::new();
+        let other = list_of(&[8, 9]);
+        let keys = other.keys().collect::<Vec<_>>();
+        empty.cursor_front_mut().splice_before(other);
+        assert_eq!(empty.keys().collect::<Vec<_>>(), keys);
+    }
+}
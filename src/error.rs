@@ -0,0 +1,77 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// The key does not refer to an element of the list.
+    InvalidKey,
+    /// The [`ListId`](crate::ListId) does not refer to a list of the pool.
+    InvalidList,
+    /// The key refers to an element that has been removed, even if its slot has been reused since.
+    StaleKey,
+    /// Two keys that must differ were the same.
+    SameKey,
+    /// The requested capacity cannot be represented.
+    CapacityExceeded,
+    /// The `prev`/`next` links or `head`/`tail` are inconsistent.
+    CorruptLinks,
+    /// The position is past the end of the list.
+    IndexOutOfBounds,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::InvalidKey => "invalid key",
+            Self::InvalidList => "invalid list",
+            Self::StaleKey => "stale key",
+            Self::SameKey => "keys must not be the same",
+            Self::CapacityExceeded => "capacity exceeded",
+            Self::CorruptLinks => "corrupt links",
+            Self::IndexOutOfBounds => "index out of bounds",
+        })
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Returned by the `try_insert_*` methods when the target key is not valid, handing the value back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsertError<T> {
+    error: Error,
+    value: T,
+}
+
+impl<T> InsertError<T> {
+    #[inline]
+    pub(crate) fn new(error: Error, value: T) -> Self {
+        Self { error, value }
+    }
+
+    #[inline]
+    pub fn error(&self) -> Error {
+        self.error
+    }
+
+    #[inline]
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> fmt::Display for InsertError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for InsertError<T> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
@@ -0,0 +1,566 @@
+use crate::{Error, InsertError, Iter, IterMut, Keys, SlabLinkedList};
+use std::iter::FusedIterator;
+use std::ops::{Index, IndexMut};
+
+/// A key that remembers which occupant of a slab slot it was issued for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Key {
+    index: usize,
+    generation: u32,
+}
+
+impl Key {
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    #[inline]
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+// One counter per slot, bumped whenever its occupant is removed. Also backs `ListId` and
+// `TimerKey`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Generations(Vec<u32>);
+
+impl Generations {
+    #[inline]
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity))
+    }
+
+    #[inline]
+    pub(crate) fn get(&self, index: usize) -> u32 {
+        self.0.get(index).copied().unwrap_or(0)
+    }
+
+    // Returns the generation for a key to a newly occupied slot.
+    #[inline]
+    pub(crate) fn issue(&mut self, index: usize) -> u32 {
+        if self.0.len() <= index {
+            self.0.resize(index + 1, 0);
+        }
+        self.0[index]
+    }
+
+    #[inline]
+    pub(crate) fn retire(&mut self, index: usize) {
+        self.0[index] = self.0[index].wrapping_add(1);
+    }
+}
+
+/// A [`SlabLinkedList`] whose keys are rejected once the element they refer to has been removed,
+/// even if the slot has been reused since.
+#[derive(Debug, Clone)]
+pub struct GenSlabLinkedList<T> {
+    list: SlabLinkedList<T>,
+    generations: Generations,
+}
+
+impl<T> Default for GenSlabLinkedList<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            list: Default::default(),
+            generations: Default::default(),
+        }
+    }
+}
+
+impl<T> GenSlabLinkedList<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    #[inline]
+    pub fn as_list(&self) -> &SlabLinkedList<T> {
+        &self.list
+    }
+
+    #[inline]
+    fn issue(&mut self, index: usize) -> Key {
+        Key {
+            index,
+            generation: self.generations.issue(index),
+        }
+    }
+
+    /// Returns the current key for an occupied slab index.
+    #[inline]
+    pub fn key(&self, index: usize) -> Option<Key> {
+        self.list.contains_key(index).then(|| Key {
+            index,
+            generation: self.generations.get(index),
+        })
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: Key) -> bool {
+        self.check(key).is_ok()
+    }
+
+    #[inline]
+    fn check(&self, key: Key) -> Result<(), Error> {
+        if self.generations.get(key.index) != key.generation {
+            Err(Error::StaleKey)
+        } else if !self.list.contains_key(key.index) {
+            Err(Error::InvalidKey)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, key: Key) -> Option<&T> {
+        if !self.contains_key(key) {
+            return None;
+        }
+        self.list.get(key.index)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, key: Key) -> Option<&mut T> {
+        if !self.contains_key(key) {
+            return None;
+        }
+        self.list.get_mut(key.index)
+    }
+
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        self.list.front()
+    }
+
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        self.list.back()
+    }
+
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.list.front_mut()
+    }
+
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.list.back_mut()
+    }
+
+    #[inline]
+    pub fn front_key(&self) -> Option<Key> {
+        self.key(self.list.front_key()?)
+    }
+
+    #[inline]
+    pub fn back_key(&self) -> Option<Key> {
+        self.key(self.list.back_key()?)
+    }
+
+    #[inline]
+    pub fn next_key(&self, key: Key) -> Option<Key> {
+        if !self.contains_key(key) {
+            return None;
+        }
+        self.key(self.list.next_key(key.index)?)
+    }
+
+    #[inline]
+    pub fn prev_key(&self, key: Key) -> Option<Key> {
+        if !self.contains_key(key) {
+            return None;
+        }
+        self.key(self.list.prev_key(key.index)?)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.list.iter()
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.list.iter_mut()
+    }
+
+    #[inline]
+    pub fn keys(&self) -> GenKeys<'_, T> {
+        GenKeys {
+            keys: self.list.keys(),
+            generations: &self.generations,
+        }
+    }
+
+    #[inline]
+    pub fn push_front(&mut self, value: T) -> Key {
+        let index = self.list.push_front(value);
+        self.issue(index)
+    }
+
+    #[inline]
+    pub fn push_back(&mut self, value: T) -> Key {
+        let index = self.list.push_back(value);
+        self.issue(index)
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn insert_before(&mut self, value: T, target_key: Key) -> Key {
+        match self.try_insert_before(value, target_key) {
+            Ok(key) => key,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn insert_after(&mut self, value: T, target_key: Key) -> Key {
+        match self.try_insert_after(value, target_key) {
+            Ok(key) => key,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    #[inline]
+    pub fn try_insert_before(&mut self, value: T, target_key: Key) -> Result<Key, InsertError<T>> {
+        if let Err(error) = self.check(target_key) {
+            return Err(InsertError::new(error, value));
+        }
+        let index = self.list.insert_before(value, target_key.index);
+        Ok(self.issue(index))
+    }
+
+    #[inline]
+    pub fn try_insert_after(&mut self, value: T, target_key: Key) -> Result<Key, InsertError<T>> {
+        if let Err(error) = self.check(target_key) {
+            return Err(InsertError::new(error, value));
+        }
+        let index = self.list.insert_after(value, target_key.index);
+        Ok(self.issue(index))
+    }
+
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<T> {
+        let key = self.front_key()?;
+        self.try_remove(key).ok()
+    }
+
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<T> {
+        let key = self.back_key()?;
+        self.try_remove(key).ok()
+    }
+
+    #[inline]
+    pub fn try_remove(&mut self, key: Key) -> Result<T, Error> {
+        self.check(key)?;
+        let value = self.list.try_remove(key.index)?;
+        self.generations.retire(key.index);
+        Ok(value)
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn remove(&mut self, key: Key) -> T {
+        match self.try_remove(key) {
+            Ok(value) => value,
+            Err(error) => panic!("{}", error),
+        }
+    }
+}
+
+impl<T> GenSlabLinkedList<T> {
+    /// Compacts the underlying slab, see [`SlabLinkedList::compact`].
+    /// The old keys of relocated elements become stale.
+    pub fn compact<F>(&mut self, mut rekey: F)
+    where
+        F: FnMut(Key, Key),
+    {
+        let generations = &mut self.generations;
+        self.list.compact(|from, to| {
+            let old = Key {
+                index: from,
+                generation: generations.get(from),
+            };
+            generations.retire(from);
+            let new = Key {
+                index: to,
+                generation: generations.get(to),
+            };
+            rekey(old, new);
+        });
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn move_to_front(&mut self, key: Key) {
+        if let Err(error) = self.try_move_to_front(key) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn move_to_back(&mut self, key: Key) {
+        if let Err(error) = self.try_move_to_back(key) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn move_before(&mut self, key: Key, target_key: Key) {
+        if let Err(error) = self.try_move_before(key, target_key) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn move_after(&mut self, key: Key, target_key: Key) {
+        if let Err(error) = self.try_move_after(key, target_key) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn swap(&mut self, key1: Key, key2: Key) {
+        if let Err(error) = self.try_swap(key1, key2) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    pub fn try_move_to_front(&mut self, key: Key) -> Result<(), Error> {
+        self.check(key)?;
+        self.list.try_move_to_front(key.index)
+    }
+
+    #[inline]
+    pub fn try_move_to_back(&mut self, key: Key) -> Result<(), Error> {
+        self.check(key)?;
+        self.list.try_move_to_back(key.index)
+    }
+
+    #[inline]
+    pub fn try_move_before(&mut self, key: Key, target_key: Key) -> Result<(), Error> {
+        self.check(key)?;
+        self.check(target_key)?;
+        self.list.try_move_before(key.index, target_key.index)
+    }
+
+    #[inline]
+    pub fn try_move_after(&mut self, key: Key, target_key: Key) -> Result<(), Error> {
+        self.check(key)?;
+        self.check(target_key)?;
+        self.list.try_move_after(key.index, target_key.index)
+    }
+
+    #[inline]
+    pub fn try_swap(&mut self, key1: Key, key2: Key) -> Result<(), Error> {
+        self.check(key1)?;
+        self.check(key2)?;
+        self.list.try_swap(key1.index, key2.index)
+    }
+}
+
+impl<T> Index<Key> for GenSlabLinkedList<T> {
+    type Output = T;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, key: Key) -> &Self::Output {
+        self.get(key).expect("invalid key")
+    }
+}
+
+impl<T> IndexMut<Key> for GenSlabLinkedList<T> {
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, key: Key) -> &mut Self::Output {
+        self.get_mut(key).expect("invalid key")
+    }
+}
+
+impl<'a, T> IntoIterator for &'a GenSlabLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut GenSlabLinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[derive(Debug)]
+pub struct GenKeys<'a, T> {
+    keys: Keys<'a, T>,
+    generations: &'a Generations,
+}
+
+impl<T> Clone for GenKeys<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            generations: self.generations,
+        }
+    }
+}
+
+impl<T> GenKeys<'_, T> {
+    #[inline]
+    fn key(&self, index: usize) -> Key {
+        Key {
+            index,
+            generation: self.generations.get(index),
+        }
+    }
+}
+
+impl<T> Iterator for GenKeys<'_, T> {
+    type Item = Key;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.keys.next()?;
+        Some(self.key(index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for GenKeys<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.keys.next_back()?;
+        Some(self.key(index))
+    }
+}
+
+impl<T> ExactSizeIterator for GenKeys<'_, T> {}
+
+impl<T> FusedIterator for GenKeys<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_key() {
+        let mut list = GenSlabLinkedList::new();
+        let a = list.push_back("a");
+        let b = list.push_back("b");
+        assert_eq!(list.remove(a), "a");
+
+        let c = list.push_front("c");
+        assert_eq!(c.index(), a.index());
+        assert_ne!(c, a);
+        assert!(!list.contains_key(a));
+        assert_eq!(list.get(a), None);
+        assert_eq!(list.get_mut(a), None);
+        assert_eq!(list.try_remove(a), Err(Error::StaleKey));
+        assert_eq!(list.next_key(a), None);
+        assert_eq!(list[c], "c");
+        assert_eq!(list.next_key(c), Some(b));
+        assert_eq!(list.keys().collect::<Vec<_>>(), [c, b]);
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn insert() {
+        let mut list = GenSlabLinkedList::new();
+        let b = list.push_back("b");
+        let a = list.insert_before("a", b);
+        let c = list.insert_after("c", b);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), ["a", "b", "c"]);
+        assert_eq!(list.front_key(), Some(a));
+        assert_eq!(list.back_key(), Some(c));
+        assert_eq!(list.pop_front(), Some("a"));
+        assert_eq!(list.pop_back(), Some("c"));
+        assert_eq!(list.key(b.index()), Some(b));
+    }
+
+    #[test]
+    fn try_insert_stale_key() {
+        let mut list = GenSlabLinkedList::new();
+        let a = list.push_back("a");
+        list.remove(a);
+        let b = list.push_back("b");
+        assert_eq!(
+            list.try_insert_before("x", a).unwrap_err().into_inner(),
+            "x"
+        );
+        assert!(list.try_insert_after("c", b).is_ok());
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn move_stale_key() {
+        let mut list = GenSlabLinkedList::new();
+        let a = list.push_back("a");
+        let b = list.push_back("b");
+        list.remove(a);
+        let c = list.push_back("c");
+        assert_eq!(list.try_move_to_front(a), Err(Error::StaleKey));
+        assert_eq!(list.try_move_before(b, a), Err(Error::StaleKey));
+        list.move_after(b, c);
+        list.swap(b, c);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), ["b", "c"]);
+    }
+
+    #[test]
+    fn compact() {
+        let mut list = GenSlabLinkedList::new();
+        let a = list.push_back("a");
+        let b = list.push_back("b");
+        list.remove(a);
+
+        let mut moves = Vec::new();
+        list.compact(|old, new| moves.push((old, new)));
+        assert_eq!(moves.len(), 1);
+        let (old, new) = moves[0];
+        assert_eq!(old, b);
+        assert_eq!(list.get(b), None);
+        assert_eq!(list.try_remove(b), Err(Error::StaleKey));
+        assert_eq!(list[new], "b");
+        assert_ne!(new, a);
+    }
+
+    #[test]
+    #[should_panic(expected = "stale key")]
+    fn insert_after_stale_key() {
+        let mut list = GenSlabLinkedList::new();
+        let a = list.push_back("a");
+        list.remove(a);
+        list.push_back("b");
+        list.insert_after("c", a);
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+
+// Maps hashes of user keys to slab keys, so that a user key is stored only once, inside
+// the list, and compared through the `eq` callback on lookup.
+#[derive(Debug, Clone)]
+pub(crate) struct HashIndex<S> {
+    map: HashMap<u64, Bucket, BuildHasherDefault<IdentityHasher>>,
+    hash_builder: S,
+}
+
+#[derive(Debug, Clone)]
+enum Bucket {
+    One(usize),
+    Many(Vec<usize>),
+}
+
+impl<S> HashIndex<S> {
+    #[inline]
+    pub(crate) fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            map: HashMap::with_capacity_and_hasher(capacity, Default::default()),
+            hash_builder,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    #[inline]
+    pub(crate) fn find(&self, hash: u64, mut eq: impl FnMut(usize) -> bool) -> Option<usize> {
+        match self.map.get(&hash)? {
+            Bucket::One(key) => eq(*key).then_some(*key),
+            Bucket::Many(keys) => keys.iter().copied().find(|&key| eq(key)),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn insert(&mut self, hash: u64, key: usize) {
+        use std::collections::hash_map::Entry;
+        match self.map.entry(hash) {
+            Entry::Vacant(entry) => {
+                entry.insert(Bucket::One(key));
+            }
+            Entry::Occupied(mut entry) => {
+                let bucket = entry.get_mut();
+                match bucket {
+                    Bucket::One(other) => *bucket = Bucket::Many(vec![*other, key]),
+                    Bucket::Many(keys) => keys.push(key),
+                }
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn remove(&mut self, hash: u64, key: usize) {
+        use std::collections::hash_map::Entry;
+        let Entry::Occupied(mut entry) = self.map.entry(hash) else {
+            return;
+        };
+        match entry.get_mut() {
+            Bucket::One(other) => {
+                if *other == key {
+                    entry.remove();
+                }
+            }
+            Bucket::Many(keys) => {
+                keys.retain(|&other| other != key);
+                if let [other] = keys[..] {
+                    entry.insert(Bucket::One(other));
+                }
+            }
+        }
+    }
+}
+
+impl<S: BuildHasher> HashIndex<S> {
+    #[inline]
+    pub(crate) fn hash<Q: Hash + ?Sized>(&self, value: &Q) -> u64 {
+        self.hash_builder.hash_one(value)
+    }
+}
+
+// The map keys are already hashes.
+#[derive(Debug, Clone, Copy, Default)]
+struct IdentityHasher(u64);
+
+impl Hasher for IdentityHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(byte);
+        }
+    }
+
+    #[inline]
+    fn write_u64(&mut self, value: u64) {
+        self.0 = value;
+    }
+}
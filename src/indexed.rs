@@ -0,0 +1,505 @@
+use crate::{Error, InsertError, Iter, IterMut, Keys, SlabLinkedList};
+use std::cmp::Ordering;
+use std::ops::{Index, IndexMut};
+
+/// A [`SlabLinkedList`] overlaid with a treap over its keys, so that positions and keys can be
+/// converted into each other in expected O(log n).
+///
+/// Each key has a tree node holding its parent, children and subtree size. Priorities come from
+/// a fixed-seed xorshift generator, so the shape of the tree is deterministic.
+#[derive(Debug, Clone)]
+pub struct IndexedList<T> {
+    list: SlabLinkedList<T>,
+    nodes: Vec<Node>,
+    root: Option<usize>,
+    rng: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Node {
+    parent: Option<usize>,
+    left: Option<usize>,
+    right: Option<usize>,
+    size: usize,
+    priority: u64,
+}
+
+impl<T> Default for IndexedList<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_capacity(0)
+    }
+}
+
+impl<T> IndexedList<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            list: SlabLinkedList::with_capacity(capacity),
+            nodes: Vec::with_capacity(capacity),
+            root: None,
+            rng: 0x853c_49e6_748f_ea9b,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    #[inline]
+    pub fn as_list(&self) -> &SlabLinkedList<T> {
+        &self.list
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.list.contains_key(key)
+    }
+
+    #[inline]
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.list.get(key)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.list.get_mut(key)
+    }
+
+    #[inline]
+    pub fn front_key(&self) -> Option<usize> {
+        self.list.front_key()
+    }
+
+    #[inline]
+    pub fn back_key(&self) -> Option<usize> {
+        self.list.back_key()
+    }
+
+    #[inline]
+    pub fn next_key(&self, key: usize) -> Option<usize> {
+        self.list.next_key(key)
+    }
+
+    #[inline]
+    pub fn prev_key(&self, key: usize) -> Option<usize> {
+        self.list.prev_key(key)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.list.iter()
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.list.iter_mut()
+    }
+
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, T> {
+        self.list.keys()
+    }
+
+    /// Returns the position of `key` in the list.
+    pub fn position_of(&self, key: usize) -> Option<usize> {
+        if !self.list.contains_key(key) {
+            return None;
+        }
+        let mut position = self.size(self.nodes[key].left);
+        let mut child = key;
+        while let Some(parent) = self.nodes[child].parent {
+            if self.nodes[parent].right == Some(child) {
+                position += self.size(self.nodes[parent].left) + 1;
+            }
+            child = parent;
+        }
+        Some(position)
+    }
+
+    /// Returns the key of the element at position `index`.
+    pub fn key_at(&self, mut index: usize) -> Option<usize> {
+        let mut node = self.root?;
+        loop {
+            let left = self.size(self.nodes[node].left);
+            match index.cmp(&left) {
+                Ordering::Less => node = self.nodes[node].left?,
+                Ordering::Equal => return Some(node),
+                Ordering::Greater => {
+                    index -= left + 1;
+                    node = self.nodes[node].right?;
+                }
+            }
+        }
+    }
+
+    #[inline]
+    pub fn get_at(&self, index: usize) -> Option<&T> {
+        self.list.get(self.key_at(index)?)
+    }
+
+    #[inline]
+    pub fn get_at_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.list.get_mut(self.key_at(index)?)
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn insert_at(&mut self, index: usize, value: T) -> usize {
+        match self.try_insert_at(index, value) {
+            Ok(key) => key,
+            Err(_) => panic!("index out of bounds"),
+        }
+    }
+
+    /// Inserts `value` so that it ends up at position `index`, which may be `len()`.
+    pub fn try_insert_at(&mut self, index: usize, value: T) -> Result<usize, InsertError<T>> {
+        if index == self.len() {
+            return Ok(self.push_back(value));
+        }
+        match self.key_at(index) {
+            Some(target_key) => Ok(self.insert_before(value, target_key)),
+            None => Err(InsertError::new(Error::IndexOutOfBounds, value)),
+        }
+    }
+
+    /// Removes the element at position `index`.
+    #[inline]
+    pub fn remove_at(&mut self, index: usize) -> Option<T> {
+        let key = self.key_at(index)?;
+        Some(self.remove(key))
+    }
+
+    #[inline]
+    pub fn push_front(&mut self, value: T) -> usize {
+        let key = self.list.push_front(value);
+        self.insert_node(key);
+        key
+    }
+
+    #[inline]
+    pub fn push_back(&mut self, value: T) -> usize {
+        let key = self.list.push_back(value);
+        self.insert_node(key);
+        key
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn insert_before(&mut self, value: T, target_key: usize) -> usize {
+        match self.try_insert_before(value, target_key) {
+            Ok(key) => key,
+            Err(_) => panic!("invalid key"),
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn insert_after(&mut self, value: T, target_key: usize) -> usize {
+        match self.try_insert_after(value, target_key) {
+            Ok(key) => key,
+            Err(_) => panic!("invalid key"),
+        }
+    }
+
+    #[inline]
+    pub fn try_insert_before(
+        &mut self,
+        value: T,
+        target_key: usize,
+    ) -> Result<usize, InsertError<T>> {
+        let key = self.list.try_insert_before(value, target_key)?;
+        self.insert_node(key);
+        Ok(key)
+    }
+
+    #[inline]
+    pub fn try_insert_after(
+        &mut self,
+        value: T,
+        target_key: usize,
+    ) -> Result<usize, InsertError<T>> {
+        let key = self.list.try_insert_after(value, target_key)?;
+        self.insert_node(key);
+        Ok(key)
+    }
+
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<T> {
+        let key = self.list.front_key()?;
+        Some(self.remove(key))
+    }
+
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<T> {
+        let key = self.list.back_key()?;
+        Some(self.remove(key))
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn remove(&mut self, key: usize) -> T {
+        self.try_remove(key).expect("invalid key")
+    }
+
+    #[inline]
+    pub fn try_remove(&mut self, key: usize) -> Result<T, Error> {
+        let value = self.list.try_remove(key)?;
+        self.remove_node(key);
+        Ok(value)
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.nodes.clear();
+        self.root = None;
+    }
+
+    #[inline]
+    fn size(&self, node: Option<usize>) -> usize {
+        node.map_or(0, |node| self.nodes[node].size)
+    }
+
+    #[inline]
+    fn update_size(&mut self, node: usize) {
+        let Node { left, right, .. } = self.nodes[node];
+        self.nodes[node].size = 1 + self.size(left) + self.size(right);
+    }
+
+    #[inline]
+    fn replace_child(&mut self, parent: Option<usize>, old: usize, new: Option<usize>) {
+        match parent {
+            None => self.root = new,
+            Some(parent) if self.nodes[parent].left == Some(old) => self.nodes[parent].left = new,
+            Some(parent) => self.nodes[parent].right = new,
+        }
+    }
+
+    // Adds the node for a key that has just been linked into the list, placing it in order
+    // right before the node of its successor.
+    fn insert_node(&mut self, key: usize) {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        if self.nodes.len() <= key {
+            self.nodes.resize(key + 1, Node::default());
+        }
+        self.nodes[key] = Node {
+            size: 1,
+            priority: self.rng,
+            ..Node::default()
+        };
+
+        let parent = match self.list.next_key(key) {
+            Some(next) => match self.nodes[next].left {
+                None => {
+                    self.nodes[next].left = Some(key);
+                    Some(next)
+                }
+                Some(left) => Some(self.attach_rightmost(left, key)),
+            },
+            None => self.root.map(|root| self.attach_rightmost(root, key)),
+        };
+        self.nodes[key].parent = parent;
+        if parent.is_none() {
+            self.root = Some(key);
+        }
+        let mut node = parent;
+        while let Some(parent) = node {
+            self.nodes[parent].size += 1;
+            node = self.nodes[parent].parent;
+        }
+        while let Some(parent) = self.nodes[key].parent {
+            if self.nodes[parent].priority >= self.nodes[key].priority {
+                break;
+            }
+            self.rotate_up(key);
+        }
+    }
+
+    #[inline]
+    fn attach_rightmost(&mut self, mut node: usize, key: usize) -> usize {
+        while let Some(right) = self.nodes[node].right {
+            node = right;
+        }
+        self.nodes[node].right = Some(key);
+        node
+    }
+
+    fn remove_node(&mut self, key: usize) {
+        while let Node {
+            left: Some(left),
+            right: Some(right),
+            ..
+        } = self.nodes[key]
+        {
+            if self.nodes[left].priority > self.nodes[right].priority {
+                self.rotate_up(left);
+            } else {
+                self.rotate_up(right);
+            }
+        }
+        let Node {
+            parent,
+            left,
+            right,
+            ..
+        } = self.nodes[key];
+        let child = left.or(right);
+        if let Some(child) = child {
+            self.nodes[child].parent = parent;
+        }
+        self.replace_child(parent, key, child);
+        let mut node = parent;
+        while let Some(parent) = node {
+            self.nodes[parent].size -= 1;
+            node = self.nodes[parent].parent;
+        }
+    }
+
+    // Rotates `node` above its parent, keeping the in-order sequence.
+    fn rotate_up(&mut self, node: usize) {
+        let parent = self.nodes[node].parent.unwrap();
+        let grandparent = self.nodes[parent].parent;
+        if self.nodes[parent].left == Some(node) {
+            let inner = self.nodes[node].right;
+            self.nodes[parent].left = inner;
+            self.nodes[node].right = Some(parent);
+            if let Some(inner) = inner {
+                self.nodes[inner].parent = Some(parent);
+            }
+        } else {
+            let inner = self.nodes[node].left;
+            self.nodes[parent].right = inner;
+            self.nodes[node].left = Some(parent);
+            if let Some(inner) = inner {
+                self.nodes[inner].parent = Some(parent);
+            }
+        }
+        self.nodes[parent].parent = Some(node);
+        self.nodes[node].parent = grandparent;
+        self.replace_child(grandparent, parent, Some(node));
+        self.update_size(parent);
+        self.update_size(node);
+    }
+}
+
+impl<T> Index<usize> for IndexedList<T> {
+    type Output = T;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, key: usize) -> &T {
+        &self.list[key]
+    }
+}
+
+impl<T> IndexMut<usize> for IndexedList<T> {
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, key: usize) -> &mut T {
+        &mut self.list[key]
+    }
+}
+
+impl<'a, T> IntoIterator for &'a IndexedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> FromIterator<T> for IndexedList<T> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for value in iter {
+            list.push_back(value);
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::Rng;
+
+    fn check<T>(list: &IndexedList<T>, keys: &[usize]) {
+        assert_eq!(list.keys().collect::<Vec<_>>(), keys);
+        for (index, &key) in keys.iter().enumerate() {
+            assert_eq!(list.position_of(key), Some(index));
+            assert_eq!(list.key_at(index), Some(key));
+        }
+        assert_eq!(list.key_at(keys.len()), None);
+        assert_eq!(list.size(list.root), keys.len());
+    }
+
+    #[test]
+    fn positions() {
+        let mut list = (0..5).collect::<IndexedList<_>>();
+        let mut keys = list.keys().collect::<Vec<_>>();
+        check(&list, &keys);
+
+        let key = list.insert_at(2, 10);
+        keys.insert(2, key);
+        let key = list.insert_at(6, 11);
+        keys.insert(6, key);
+        let key = list.insert_after(12, keys[0]);
+        keys.insert(1, key);
+        check(&list, &keys);
+        assert_eq!(list.get_at(3), Some(&10));
+
+        assert_eq!(list.remove_at(3), Some(10));
+        keys.remove(3);
+        assert_eq!(list.remove_at(100), None);
+        assert_eq!(list.pop_front(), Some(0));
+        keys.remove(0);
+        check(&list, &keys);
+
+        assert_eq!(
+            list.try_insert_at(100, 13).unwrap_err().error(),
+            Error::IndexOutOfBounds
+        );
+        assert_eq!(list.position_of(100), None);
+        list.clear();
+        check(&list, &[]);
+    }
+
+    #[test]
+    fn random_edits() {
+        let mut rng = Rng::new(0x2545_f491_4f6c_dd1d);
+        let mut rand = || rng.next_u64() as usize;
+        let mut list = IndexedList::new();
+        let mut keys = Vec::new();
+        for i in 0..2000 {
+            if rand() % 3 == 0 && !keys.is_empty() {
+                let index = rand() % keys.len();
+                assert!(list.remove_at(index).is_some());
+                keys.remove(index);
+            } else {
+                let index = rand() % (keys.len() + 1);
+                keys.insert(index, list.insert_at(index, i));
+            }
+            if i % 100 == 0 {
+                check(&list, &keys);
+            }
+        }
+        check(&list, &keys);
+    }
+}
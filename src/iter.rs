@@ -0,0 +1,690 @@
+use crate::{Item, SlabLinkedList};
+use slab::Slab;
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::iter::FusedIterator;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Walk {
+    head: Option<usize>,
+    tail: Option<usize>,
+    len: usize,
+    linear: bool,
+}
+
+// The links of a slab entry that `Walk` follows.
+pub(crate) trait Links {
+    fn next(&self) -> Option<usize>;
+    fn prev(&self) -> Option<usize>;
+}
+
+impl<T> Links for Item<T> {
+    #[inline]
+    fn next(&self) -> Option<usize> {
+        self.next
+    }
+
+    #[inline]
+    fn prev(&self) -> Option<usize> {
+        self.prev
+    }
+}
+
+// The value of a slab entry. Mutable iterators walk the links through a shared borrow of
+// the slab and take each value out of its cell, so they cost nothing beyond the walk itself.
+pub(crate) struct ValueCell<T>(UnsafeCell<T>);
+
+// A `&ValueCell` only gives out `&T`, except for mutable iterators, which hold the unique
+// borrow of the slab.
+unsafe impl<T: Sync> Sync for ValueCell<T> {}
+
+impl<T> ValueCell<T> {
+    #[inline]
+    pub(crate) fn new(value: T) -> Self {
+        Self(UnsafeCell::new(value))
+    }
+
+    #[inline]
+    pub(crate) fn get(&self) -> &T {
+        unsafe { &*self.0.get() }
+    }
+
+    #[inline]
+    pub(crate) fn get_mut(&mut self) -> &mut T {
+        self.0.get_mut()
+    }
+
+    #[inline]
+    pub(crate) fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+
+    // Only dereferenced mutably while the slab holding the cell is uniquely borrowed, and at
+    // most once per borrow.
+    #[inline]
+    pub(crate) fn as_ptr(&self) -> *mut T {
+        self.0.get()
+    }
+}
+
+impl<T: Clone> Clone for ValueCell<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.get().clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ValueCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl Walk {
+    #[inline]
+    pub(crate) fn new<T>(list: &SlabLinkedList<T>) -> Self {
+        Self {
+            head: list.head,
+            tail: list.tail,
+            len: list.len(),
+            linear: list.linear,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn linked(head: Option<usize>, tail: Option<usize>, len: usize) -> Self {
+        Self {
+            head,
+            tail,
+            len,
+            linear: false,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub(crate) fn next<I: Links>(&mut self, slab: &Slab<I>) -> Option<usize> {
+        self.next_by(|key| unsafe { slab.get_unchecked(key) }.next())
+    }
+
+    #[inline]
+    pub(crate) fn next_back<I: Links>(&mut self, slab: &Slab<I>) -> Option<usize> {
+        self.next_back_by(|key| unsafe { slab.get_unchecked(key) }.prev())
+    }
+
+    #[inline]
+    pub(crate) fn next_by(&mut self, next: impl FnOnce(usize) -> Option<usize>) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        let key = self.head?;
+        self.len -= 1;
+        self.head = if self.linear {
+            Some(key + 1)
+        } else {
+            next(key)
+        };
+        Some(key)
+    }
+
+    #[inline]
+    pub(crate) fn next_back_by(
+        &mut self,
+        prev: impl FnOnce(usize) -> Option<usize>,
+    ) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        let key = self.tail?;
+        self.len -= 1;
+        self.tail = if self.linear {
+            key.checked_sub(1)
+        } else {
+            prev(key)
+        };
+        Some(key)
+    }
+}
+
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    slab: &'a Slab<Item<T>>,
+    walk: Walk,
+}
+
+impl<'a, T> Iter<'a, T> {
+    #[inline]
+    pub(crate) fn new(list: &'a SlabLinkedList<T>) -> Self {
+        Self {
+            slab: &list.slab,
+            walk: Walk::new(list),
+        }
+    }
+}
+
+impl<T> Clone for Iter<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            slab: self.slab,
+            walk: self.walk,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.walk.next(self.slab)?;
+        Some(unsafe { self.slab.get_unchecked(key) }.value.get())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.walk.len(), Some(self.walk.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.walk.next_back(self.slab)?;
+        Some(unsafe { self.slab.get_unchecked(key) }.value.get())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+#[derive(Debug)]
+pub struct IterMut<'a, T> {
+    slab: &'a Slab<Item<T>>,
+    walk: Walk,
+}
+
+// Holds the unique borrow of the list, like `&'a mut T` would.
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+
+impl<'a, T> IterMut<'a, T> {
+    #[inline]
+    pub(crate) fn new(list: &'a mut SlabLinkedList<T>) -> Self {
+        Self {
+            walk: Walk::new(list),
+            slab: &list.slab,
+        }
+    }
+
+    #[inline]
+    fn next_entry(&mut self) -> Option<(usize, &'a mut T)> {
+        let key = self.walk.next(self.slab)?;
+        Some((key, unsafe {
+            &mut *self.slab.get_unchecked(key).value.as_ptr()
+        }))
+    }
+
+    #[inline]
+    fn next_back_entry(&mut self) -> Option<(usize, &'a mut T)> {
+        let key = self.walk.next_back(self.slab)?;
+        Some((key, unsafe {
+            &mut *self.slab.get_unchecked(key).value.as_ptr()
+        }))
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_entry().map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.walk.len(), Some(self.walk.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.next_back_entry().map(|(_, value)| value)
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+#[derive(Debug)]
+pub struct Keys<'a, T> {
+    slab: &'a Slab<Item<T>>,
+    walk: Walk,
+}
+
+impl<'a, T> Keys<'a, T> {
+    #[inline]
+    pub(crate) fn new(list: &'a SlabLinkedList<T>) -> Self {
+        Self {
+            slab: &list.slab,
+            walk: Walk::new(list),
+        }
+    }
+}
+
+impl<T> Clone for Keys<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            slab: self.slab,
+            walk: self.walk,
+        }
+    }
+}
+
+impl<T> Iterator for Keys<'_, T> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.walk.next(self.slab)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.walk.len(), Some(self.walk.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for Keys<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.walk.next_back(self.slab)
+    }
+}
+
+impl<T> ExactSizeIterator for Keys<'_, T> {}
+
+impl<T> FusedIterator for Keys<'_, T> {}
+
+#[derive(Debug)]
+pub struct IterWithKeys<'a, T> {
+    slab: &'a Slab<Item<T>>,
+    walk: Walk,
+}
+
+impl<'a, T> IterWithKeys<'a, T> {
+    #[inline]
+    pub(crate) fn new(list: &'a SlabLinkedList<T>) -> Self {
+        Self {
+            slab: &list.slab,
+            walk: Walk::new(list),
+        }
+    }
+}
+
+impl<T> Clone for IterWithKeys<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            slab: self.slab,
+            walk: self.walk,
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterWithKeys<'a, T> {
+    type Item = (usize, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.walk.next(self.slab)?;
+        Some((key, unsafe { self.slab.get_unchecked(key) }.value.get()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.walk.len(), Some(self.walk.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterWithKeys<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.walk.next_back(self.slab)?;
+        Some((key, unsafe { self.slab.get_unchecked(key) }.value.get()))
+    }
+}
+
+impl<T> ExactSizeIterator for IterWithKeys<'_, T> {}
+
+impl<T> FusedIterator for IterWithKeys<'_, T> {}
+
+#[derive(Debug)]
+pub struct IterMutWithKeys<'a, T> {
+    inner: IterMut<'a, T>,
+}
+
+impl<'a, T> IterMutWithKeys<'a, T> {
+    #[inline]
+    pub(crate) fn new(list: &'a mut SlabLinkedList<T>) -> Self {
+        Self {
+            inner: IterMut::new(list),
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterMutWithKeys<'a, T> {
+    type Item = (usize, &'a mut T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_entry()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMutWithKeys<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back_entry()
+    }
+}
+
+impl<T> ExactSizeIterator for IterMutWithKeys<'_, T> {}
+
+impl<T> FusedIterator for IterMutWithKeys<'_, T> {}
+
+#[derive(Debug)]
+pub struct Drain<'a, T> {
+    list: &'a mut SlabLinkedList<T>,
+}
+
+impl<'a, T> Drain<'a, T> {
+    #[inline]
+    pub(crate) fn new(list: &'a mut SlabLinkedList<T>) -> Self {
+        Self { list }
+    }
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len(), Some(self.list.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.list.clear();
+    }
+}
+
+#[derive(Debug)]
+pub struct ExtractIf<'a, T, F> {
+    list: &'a mut SlabLinkedList<T>,
+    next: Option<usize>,
+    pred: F,
+}
+
+impl<'a, T, F> ExtractIf<'a, T, F> {
+    #[inline]
+    pub(crate) fn new(list: &'a mut SlabLinkedList<T>, pred: F) -> Self {
+        Self {
+            next: list.head,
+            list,
+            pred,
+        }
+    }
+}
+
+impl<T, F> Iterator for ExtractIf<'_, T, F>
+where
+    F: FnMut(usize, &mut T) -> bool,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(key) = self.next {
+            let item = unsafe { self.list.slab.get_unchecked_mut(key) };
+            self.next = item.next;
+            if (self.pred)(key, item.value.get_mut()) {
+                return Some(self.list.remove(key));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.list.len()))
+    }
+}
+
+impl<T, F> FusedIterator for ExtractIf<'_, T, F> where F: FnMut(usize, &mut T) -> bool {}
+
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    list: SlabLinkedList<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len(), Some(self.list.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for SlabLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SlabLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SlabLinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list() -> SlabLinkedList<&'static str> {
+        let mut list = SlabLinkedList::new();
+        list.push_back("a");
+        list.push_front("b");
+        list.push_back("c");
+        let i = list.push_front("d");
+        list.insert_after("e", i);
+        // "d", "e", "b", "a", "c"
+        list
+    }
+
+    #[test]
+    fn iter() {
+        let list = list();
+        assert_eq!(list.iter().len(), 5);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            ["d", "e", "b", "a", "c"]
+        );
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            ["c", "a", "b", "e", "d"]
+        );
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&"d"));
+        assert_eq!(iter.next_back(), Some(&"c"));
+        assert_eq!(iter.next(), Some(&"e"));
+        assert_eq!(iter.next_back(), Some(&"a"));
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next_back(), Some(&"b"));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut list = list();
+        for value in &mut list {
+            *value = if *value == "a" { "x" } else { *value };
+        }
+        let mut iter = list.iter_mut();
+        assert_eq!(iter.next_back(), Some(&mut "c"));
+        assert_eq!(iter.next_back(), Some(&mut "x"));
+        assert_eq!(iter.next(), Some(&mut "d"));
+        assert_eq!(iter.len(), 2);
+    }
+
+    // Run under Miri. The linear list is walked by key and the relinked one by its links, in
+    // both cases while the values handed out earlier are still borrowed.
+    #[test]
+    fn iter_mut_references_coexist() {
+        let mut linear = SlabLinkedList::from([1, 2, 3]);
+        let mut iter = linear.iter_mut();
+        let a = iter.next().unwrap();
+        let b = iter.next().unwrap();
+        let c = iter.next_back().unwrap();
+        *a += *b + *c;
+        assert_eq!(linear.iter().copied().collect::<Vec<_>>(), [6, 2, 3]);
+
+        let mut list = SlabLinkedList::from([1, 2, 3]);
+        list.move_to_front(2);
+        let mut refs = list.iter_mut_with_keys().collect::<Vec<_>>();
+        for (key, value) in &mut refs {
+            **value *= 10 + *key as i32;
+        }
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [36, 10, 22]);
+    }
+
+    #[test]
+    fn keys() {
+        let mut list = SlabLinkedList::new();
+        let a = list.push_back("a");
+        let b = list.push_front("b");
+        let c = list.insert_after("c", b);
+        // "b", "c", "a"
+        assert_eq!(list.keys().collect::<Vec<_>>(), [b, c, a]);
+        assert_eq!(list.keys().rev().collect::<Vec<_>>(), [a, c, b]);
+        assert_eq!(
+            list.iter_with_keys().collect::<Vec<_>>(),
+            [(b, &"b"), (c, &"c"), (a, &"a")]
+        );
+        assert_eq!(list.iter_with_keys().next_back(), Some((a, &"a")));
+        for (key, value) in list.iter_mut_with_keys() {
+            if key == c {
+                *value = "x";
+            }
+        }
+        assert_eq!(list.get(c), Some(&"x"));
+        assert_eq!(list.iter_mut_with_keys().next_back(), Some((a, &mut "a")));
+    }
+
+    #[test]
+    fn drain() {
+        let mut list = list();
+        let mut drain = list.drain();
+        assert_eq!(drain.len(), 5);
+        assert_eq!(drain.next(), Some("d"));
+        assert_eq!(drain.next_back(), Some("c"));
+        drop(drain);
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+
+        let mut list = self::list();
+        assert_eq!(list.drain().collect::<Vec<_>>(), ["d", "e", "b", "a", "c"]);
+        assert!(list.is_empty());
+        list.push_back("f");
+        assert_eq!(list.iter().collect::<Vec<_>>(), [&"f"]);
+    }
+
+    #[test]
+    fn extract_if() {
+        let mut list = list();
+        let extracted = list.extract_if(|_, value| *value < "c").collect::<Vec<_>>();
+        assert_eq!(extracted, ["b", "a"]);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), ["d", "e", "c"]);
+
+        assert_eq!(list.extract_if(|_, value| *value != "e").next(), Some("d"));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), ["e", "c"]);
+        assert_eq!(list.validate(), Ok(()));
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut iter = list().into_iter();
+        assert_eq!(iter.next(), Some("d"));
+        assert_eq!(iter.next_back(), Some("c"));
+        assert_eq!(iter.collect::<Vec<_>>(), ["e", "b", "a"]);
+    }
+}
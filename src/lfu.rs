@@ -0,0 +1,310 @@
+use crate::index::HashIndex;
+use crate::{ListId, ListPool, SlabLinkedList};
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+/// A least-frequently-used cache with O(1) operations.
+///
+/// Entries with the same access count share one list of a [`ListPool`], most recently used
+/// first, and those lists are linked in ascending order of frequency. A hit moves the entry
+/// into the next bucket without changing its key; eviction takes the back of the lowest
+/// bucket, so ties are broken by recency.
+#[derive(Debug, Clone)]
+pub struct LfuCache<K, V, S = RandomState> {
+    entries: ListPool<Slot<K, V>>,
+    buckets: SlabLinkedList<Bucket>,
+    index: HashIndex<S>,
+    cap: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Slot<K, V> {
+    key: K,
+    value: V,
+    bucket: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Bucket {
+    freq: usize,
+    list: ListId,
+}
+
+impl<K: Hash + Eq, V> LfuCache<K, V> {
+    #[inline]
+    pub fn new(cap: usize) -> Self {
+        Self::with_hasher(cap, Default::default())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> LfuCache<K, V, S> {
+    #[inline]
+    pub fn with_hasher(cap: usize, hash_builder: S) -> Self {
+        Self {
+            entries: ListPool::with_capacity(cap),
+            buckets: SlabLinkedList::new(),
+            index: HashIndex::with_capacity_and_hasher(cap, hash_builder),
+            cap,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[inline]
+    pub fn cap(&self) -> usize {
+        self.cap
+    }
+
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        self.index.hasher()
+    }
+
+    #[inline]
+    fn find<Q>(&self, k: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.index.hash(k);
+        self.index
+            .find(hash, |key| self.entries[key].key.borrow() == k)
+    }
+
+    #[inline]
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(k).is_some()
+    }
+
+    /// Returns the value and counts an access.
+    #[inline]
+    pub fn get<Q>(&mut self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let key = self.find(k)?;
+        self.touch(key);
+        Some(&self.entries[key].value)
+    }
+
+    /// Returns the value and counts an access.
+    #[inline]
+    pub fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let key = self.find(k)?;
+        self.touch(key);
+        Some(&mut self.entries[key].value)
+    }
+
+    /// Returns the value without counting an access.
+    #[inline]
+    pub fn peek<Q>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let key = self.find(k)?;
+        Some(&self.entries[key].value)
+    }
+
+    /// Returns how many times `k` has been accessed, counting the insertion.
+    #[inline]
+    pub fn frequency<Q>(&self, k: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let key = self.find(k)?;
+        Some(self.buckets[self.entries[key].bucket].freq)
+    }
+
+    /// Returns the entry that would be evicted next.
+    #[inline]
+    pub fn peek_lfu(&self) -> Option<(&K, &V)> {
+        let bucket = self.buckets.front()?;
+        let slot = self.entries.back(bucket.list)?;
+        Some((&slot.key, &slot.value))
+    }
+
+    /// Inserts an entry with a frequency of one, or replaces the value and counts an access if
+    /// `k` is already present.
+    ///
+    /// Returns the replaced entry, otherwise the least frequently used entry if it had to be
+    /// evicted to stay within the capacity.
+    pub fn put(&mut self, k: K, v: V) -> Option<(K, V)> {
+        let hash = self.index.hash(&k);
+        if let Some(key) = self.index.find(hash, |key| self.entries[key].key == k) {
+            self.touch(key);
+            let slot = &mut self.entries[key];
+            let key = std::mem::replace(&mut slot.key, k);
+            let value = std::mem::replace(&mut slot.value, v);
+            return Some((key, value));
+        }
+        if self.cap == 0 {
+            return Some((k, v));
+        }
+        let evicted = if self.len() >= self.cap {
+            self.pop_lfu()
+        } else {
+            None
+        };
+        let bucket = match self.buckets.front_key() {
+            Some(bucket) if self.buckets[bucket].freq == 1 => bucket,
+            _ => {
+                let list = self.entries.create_list();
+                self.buckets.push_front(Bucket { freq: 1, list })
+            }
+        };
+        let list = self.buckets[bucket].list;
+        let key = self.entries.push_front(
+            list,
+            Slot {
+                key: k,
+                value: v,
+                bucket,
+            },
+        );
+        self.index.insert(hash, key);
+        evicted
+    }
+
+    #[inline]
+    pub fn pop<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let key = self.find(k)?;
+        Some(self.remove_slot(key).1)
+    }
+
+    #[inline]
+    pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+        let list = self.buckets.front()?.list;
+        let key = self.entries.back_key(list)?;
+        Some(self.remove_slot(key))
+    }
+
+    /// Changes the capacity, evicting least frequently used entries if it shrinks.
+    pub fn resize(&mut self, cap: usize) {
+        while self.len() > cap {
+            self.pop_lfu();
+        }
+        self.cap = cap;
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries = ListPool::new();
+        self.buckets.clear();
+        self.index.clear();
+    }
+
+    // Moves `key` to the front of the bucket for the next frequency.
+    fn touch(&mut self, key: usize) {
+        let bucket = self.entries[key].bucket;
+        let freq = self.buckets[bucket].freq + 1;
+        let next = match self.buckets.next_key(bucket) {
+            Some(next) if self.buckets[next].freq == freq => next,
+            _ => {
+                let list = self.entries.create_list();
+                self.buckets.insert_after(Bucket { freq, list }, bucket)
+            }
+        };
+        self.entries.move_to_front(key, self.buckets[next].list);
+        self.entries[key].bucket = next;
+        self.release_bucket(bucket);
+    }
+
+    fn remove_slot(&mut self, key: usize) -> (K, V) {
+        let slot = self.entries.remove(key);
+        self.index.remove(self.index.hash(&slot.key), key);
+        self.release_bucket(slot.bucket);
+        (slot.key, slot.value)
+    }
+
+    #[inline]
+    fn release_bucket(&mut self, bucket: usize) {
+        let list = self.buckets[bucket].list;
+        if self.entries.list_is_empty(list) {
+            self.entries.remove_list(list);
+            self.buckets.remove(bucket);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_frequent() {
+        let mut cache = LfuCache::new(3);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("c", 3);
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.get("b"), Some(&2));
+        assert_eq!(cache.frequency("a"), Some(3));
+        assert_eq!(cache.frequency("b"), Some(2));
+        assert_eq!(cache.frequency("c"), Some(1));
+
+        assert_eq!(cache.peek_lfu(), Some((&"c", &3)));
+        assert_eq!(cache.put("d", 4), Some(("c", 3)));
+        assert_eq!(cache.frequency("d"), Some(1));
+        assert_eq!(cache.put("e", 5), Some(("d", 4)));
+
+        assert_eq!(cache.peek("e"), Some(&5));
+        assert_eq!(cache.frequency("e"), Some(1));
+        cache.get("e");
+        // "b" and "e" both have a frequency of two, and "b" was used less recently.
+        assert_eq!(cache.put("f", 6), Some(("b", 2)));
+        assert_eq!(cache.frequency("e"), Some(2));
+    }
+
+    #[test]
+    fn keys_are_stable_across_buckets() {
+        let mut cache = LfuCache::new(2);
+        cache.put(1, "one");
+        let key = cache.find(&1).unwrap();
+        for _ in 0..5 {
+            *cache.get_mut(&1).unwrap() = "uno";
+            assert_eq!(cache.find(&1), Some(key));
+        }
+        assert_eq!(cache.frequency(&1), Some(6));
+        assert_eq!(cache.put(1, "eins"), Some((1, "uno")));
+        assert_eq!(cache.frequency(&1), Some(7));
+        assert_eq!(cache.buckets.len(), 1);
+
+        cache.put(2, "two");
+        assert_eq!(cache.buckets.len(), 2);
+        assert_eq!(cache.pop(&2), Some("two"));
+        assert_eq!(cache.buckets.len(), 1);
+
+        cache.put(3, "three");
+        cache.resize(1);
+        assert_eq!(cache.peek(&1), Some(&"eins"));
+        assert_eq!(cache.len(), 1);
+        cache.clear();
+        assert!(cache.is_empty() && cache.buckets.is_empty());
+        assert_eq!(cache.pop_lfu(), None);
+    }
+}
@@ -1,10 +1,49 @@
+use iter::ValueCell;
 use slab::Slab;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Index, IndexMut};
 
-#[derive(Debug)]
+mod cursor;
+mod error;
+mod generational;
+mod index;
+mod indexed;
+mod iter;
+mod lfu;
+mod lru;
+mod map;
+mod multi;
+mod order;
+mod pool;
+mod splice;
+#[cfg(test)]
+mod test_rng;
+mod timer;
+pub use cursor::{Cursor, CursorMut};
+pub use error::{Error, InsertError};
+pub use generational::{GenKeys, GenSlabLinkedList, Key};
+pub use indexed::IndexedList;
+pub use iter::{Drain, ExtractIf, IntoIter, Iter, IterMut, IterMutWithKeys, IterWithKeys, Keys};
+pub use lfu::LfuCache;
+pub use lru::{LruCache, LruIter, LruIterMut};
+pub use map::{
+    Entry, OccupiedEntry, OrderedMap, OrderedMapIntoIter, OrderedMapIter, OrderedMapIterMut,
+    OrderedMapKeys, OrderedMapValues, OrderedMapValuesMut, VacantEntry,
+};
+pub use multi::{LaneIter, LaneKeys, SlabMultiList};
+pub use order::LabeledList;
+pub use pool::{ListId, ListPool, ListPoolIter, ListPoolIterMut, ListPoolKeys};
+pub use timer::{Expired, TimerKey, TimerWheel};
+
+#[derive(Clone)]
 pub struct SlabLinkedList<T> {
     slab: Slab<Item<T>>,
     head: Option<usize>,
     tail: Option<usize>,
+    // keys are `0..len` in list order
+    linear: bool,
 }
 
 impl<T> Default for SlabLinkedList<T> {
@@ -14,6 +53,7 @@ impl<T> Default for SlabLinkedList<T> {
             slab: Default::default(),
             head: None,
             tail: None,
+            linear: true,
         }
     }
 }
@@ -24,6 +64,51 @@ impl<T> SlabLinkedList<T> {
         Self::default()
     }
 
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slab: Slab::with_capacity(capacity),
+            head: None,
+            tail: None,
+            linear: true,
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.slab.capacity()
+    }
+
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.slab.reserve(additional);
+    }
+
+    #[inline]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.slab.reserve_exact(additional);
+    }
+
+    /// Like [`reserve`](Self::reserve), but returns [`Error::CapacityExceeded`] instead of
+    /// panicking when the new capacity cannot be represented. `Slab` has no fallible
+    /// allocation, so running out of memory still aborts.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), Error> {
+        let max = isize::MAX as usize / std::mem::size_of::<Item<T>>().max(1);
+        match self.slab.len().checked_add(additional) {
+            Some(capacity) if capacity <= max => {
+                self.slab.reserve(additional);
+                Ok(())
+            }
+            _ => Err(Error::CapacityExceeded),
+        }
+    }
+
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.slab.shrink_to_fit();
+    }
+
     #[inline]
     pub fn len(&self) -> usize {
         self.slab.len()
@@ -34,9 +119,127 @@ impl<T> SlabLinkedList<T> {
         self.slab.is_empty()
     }
 
+    #[inline]
+    pub fn front_key(&self) -> Option<usize> {
+        self.head
+    }
+
+    #[inline]
+    pub fn back_key(&self) -> Option<usize> {
+        self.tail
+    }
+
+    #[inline]
+    pub fn next_key(&self, key: usize) -> Option<usize> {
+        self.slab.get(key)?.next
+    }
+
+    #[inline]
+    pub fn prev_key(&self, key: usize) -> Option<usize> {
+        self.slab.get(key)?.prev
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.slab.contains(key)
+    }
+
+    #[inline]
+    pub fn is_front(&self, key: usize) -> bool {
+        self.head == Some(key)
+    }
+
+    #[inline]
+    pub fn is_back(&self, key: usize) -> bool {
+        self.tail == Some(key)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self)
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self)
+    }
+
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, T> {
+        Keys::new(self)
+    }
+
+    #[inline]
+    pub fn iter_with_keys(&self) -> IterWithKeys<'_, T> {
+        IterWithKeys::new(self)
+    }
+
+    #[inline]
+    pub fn iter_mut_with_keys(&mut self) -> IterMutWithKeys<'_, T> {
+        IterMutWithKeys::new(self)
+    }
+
+    #[inline]
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor::new(self, self.head)
+    }
+
+    #[inline]
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor::new(self, self.tail)
+    }
+
+    #[inline]
+    pub fn cursor_at(&self, key: usize) -> Option<Cursor<'_, T>> {
+        self.contains_key(key).then(|| Cursor::new(self, Some(key)))
+    }
+
+    #[inline]
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        let current = self.head;
+        CursorMut::new(self, current)
+    }
+
+    #[inline]
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        let current = self.tail;
+        CursorMut::new(self, current)
+    }
+
+    #[inline]
+    pub fn cursor_at_mut(&mut self, key: usize) -> Option<CursorMut<'_, T>> {
+        self.contains_key(key)
+            .then(|| CursorMut::new(self, Some(key)))
+    }
+
     #[inline]
     pub fn get(&self, key: usize) -> Option<&T> {
-        self.slab.get(key).map(|item| &item.value)
+        self.slab.get(key).map(|item| item.value.get())
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.slab.get_mut(key).map(|item| item.value.get_mut())
+    }
+
+    /// Returns `None` if either key is invalid or both keys are the same.
+    #[inline]
+    pub fn get2_mut(&mut self, key1: usize, key2: usize) -> Option<(&mut T, &mut T)> {
+        // `Slab::get2_mut` panics instead of returning `None` on out of range or equal keys
+        if key1 == key2 || !self.slab.contains(key1) || !self.slab.contains(key2) {
+            return None;
+        }
+        self.slab
+            .get2_mut(key1, key2)
+            .map(|(item1, item2)| (item1.value.get_mut(), item2.value.get_mut()))
+    }
+
+    #[inline]
+    pub fn try_get2_mut(&mut self, key1: usize, key2: usize) -> Result<(&mut T, &mut T), Error> {
+        if key1 == key2 {
+            return Err(Error::SameKey);
+        }
+        self.get2_mut(key1, key2).ok_or(Error::InvalidKey)
     }
 
     #[inline]
@@ -45,7 +248,7 @@ impl<T> SlabLinkedList<T> {
             None => None,
             Some(key) => {
                 let item = unsafe { self.slab.get_unchecked(key) };
-                Some(&item.value)
+                Some(item.value.get())
             }
         }
     }
@@ -56,7 +259,29 @@ impl<T> SlabLinkedList<T> {
             None => None,
             Some(key) => {
                 let item = unsafe { self.slab.get_unchecked(key) };
-                Some(&item.value)
+                Some(item.value.get())
+            }
+        }
+    }
+
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        match self.head {
+            None => None,
+            Some(key) => {
+                let item = unsafe { self.slab.get_unchecked_mut(key) };
+                Some(item.value.get_mut())
+            }
+        }
+    }
+
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        match self.tail {
+            None => None,
+            Some(key) => {
+                let item = unsafe { self.slab.get_unchecked_mut(key) };
+                Some(item.value.get_mut())
             }
         }
     }
@@ -64,9 +289,63 @@ impl<T> SlabLinkedList<T> {
     #[inline]
     #[track_caller]
     pub fn insert_before(&mut self, value: T, target_key: usize) -> usize {
+        match self.try_insert_before(value, target_key) {
+            Ok(key) => key,
+            Err(_) => panic!("invalid key"),
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn insert_after(&mut self, value: T, target_key: usize) -> usize {
+        match self.try_insert_after(value, target_key) {
+            Ok(key) => key,
+            Err(_) => panic!("invalid key"),
+        }
+    }
+
+    #[inline]
+    pub fn try_insert_before(
+        &mut self,
+        value: T,
+        target_key: usize,
+    ) -> Result<usize, InsertError<T>> {
+        if !self.slab.contains(target_key) {
+            return Err(InsertError::new(Error::InvalidKey, value));
+        }
+        let key = self.insert_item(value);
+        self.link_before(key, target_key);
+        Ok(key)
+    }
+
+    #[inline]
+    pub fn try_insert_after(
+        &mut self,
+        value: T,
+        target_key: usize,
+    ) -> Result<usize, InsertError<T>> {
+        if !self.slab.contains(target_key) {
+            return Err(InsertError::new(Error::InvalidKey, value));
+        }
+        let key = self.insert_item(value);
+        self.link_after(key, target_key);
+        Ok(key)
+    }
+
+    #[inline]
+    fn insert_item(&mut self, value: T) -> usize {
         let key = self.slab.insert(Item::from(value));
-        let (item, target_item) = self.slab.get2_mut(key, target_key).expect("invalid key");
-        item.key.replace(key);
+        unsafe {
+            self.slab.get_unchecked_mut(key).key.replace(key);
+        }
+        key
+    }
+
+    #[inline]
+    fn link_before(&mut self, key: usize, target_key: usize) {
+        self.linear = false;
+        let (item, target_item) = self.slab.get2_mut(key, target_key).unwrap();
+        debug_assert!(item.prev.is_none() && item.next.is_none());
         item.next.replace(target_key);
 
         match target_item.prev.replace(key) {
@@ -75,20 +354,18 @@ impl<T> SlabLinkedList<T> {
                 assert_eq!(self.head.replace(key), Some(target_key));
             }
             Some(prev) => {
+                item.prev.replace(prev);
                 let prev_item = self.slab.get_mut(prev).unwrap();
                 assert_eq!(prev_item.next.replace(key), Some(target_key));
             }
         }
-
-        key
     }
 
     #[inline]
-    #[track_caller]
-    pub fn insert_after(&mut self, value: T, target_key: usize) -> usize {
-        let key = self.slab.insert(Item::from(value));
-        let (item, target_item) = self.slab.get2_mut(key, target_key).expect("invalid key");
-        item.key.replace(key);
+    fn link_after(&mut self, key: usize, target_key: usize) {
+        self.linear &= self.tail == Some(target_key) && key + 1 == self.slab.len();
+        let (item, target_item) = self.slab.get2_mut(key, target_key).unwrap();
+        debug_assert!(item.prev.is_none() && item.next.is_none());
         item.prev.replace(target_key);
 
         match target_item.next.replace(key) {
@@ -97,12 +374,11 @@ impl<T> SlabLinkedList<T> {
                 assert_eq!(self.tail.replace(key), Some(target_key));
             }
             Some(next) => {
+                item.next.replace(next);
                 let next_item = self.slab.get_mut(next).unwrap();
                 assert_eq!(next_item.prev.replace(key), Some(target_key));
             }
         }
-
-        key
     }
 
     #[inline]
@@ -110,12 +386,10 @@ impl<T> SlabLinkedList<T> {
         debug_assert!(self.slab.is_empty());
         debug_assert!(self.head.is_none());
         debug_assert!(self.tail.is_none());
-        let key = self.slab.insert(Item::from(value));
+        let key = self.insert_item(value);
         self.head.replace(key);
         self.tail.replace(key);
-        unsafe {
-            self.slab.get_unchecked_mut(key).key.replace(key);
-        }
+        self.linear = key == 0;
         key
     }
 
@@ -135,6 +409,49 @@ impl<T> SlabLinkedList<T> {
         }
     }
 
+    /// Appends every value at the back and returns their keys in order.
+    pub fn extend_returning_keys<I>(&mut self, iter: I) -> Vec<usize>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let iter = iter.into_iter();
+        let mut keys = Vec::with_capacity(iter.size_hint().0);
+        self.extend_back(iter, |key| keys.push(key));
+        keys
+    }
+
+    // links each new item directly to the previous one instead of going through `link_after`
+    fn extend_back<I, F>(&mut self, iter: I, mut on_insert: F)
+    where
+        I: IntoIterator<Item = T>,
+        F: FnMut(usize),
+    {
+        let iter = iter.into_iter();
+        self.slab.reserve(iter.size_hint().0);
+        for value in iter {
+            let key = self.slab.vacant_key();
+            self.slab.insert(Item {
+                value: ValueCell::new(value),
+                key: Some(key),
+                prev: self.tail,
+                next: None,
+            });
+            match self.tail.replace(key) {
+                None => {
+                    self.head.replace(key);
+                    self.linear = key == 0;
+                }
+                Some(tail) => {
+                    unsafe { self.slab.get_unchecked_mut(tail) }
+                        .next
+                        .replace(key);
+                    self.linear &= key + 1 == self.slab.len();
+                }
+            }
+            on_insert(key);
+        }
+    }
+
     #[inline]
     #[track_caller]
     pub fn pop_front(&mut self) -> Option<T> {
@@ -152,17 +469,37 @@ impl<T> SlabLinkedList<T> {
     }
 
     #[inline]
-    pub fn try_remove(&mut self, key: usize) -> Option<T> {
-        let item = self.slab.try_remove(key)?;
+    pub fn try_remove(&mut self, key: usize) -> Result<T, Error> {
+        if !self.slab.contains(key) {
+            return Err(Error::InvalidKey);
+        }
+        self.unlink(key);
 
         let Item {
             value,
             key: stored_key,
             prev,
             next,
-        } = item;
+        } = self.slab.remove(key);
 
         debug_assert_eq!(stored_key, Some(key));
+        debug_assert!(prev.is_none() && next.is_none());
+
+        Ok(value.into_inner())
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn remove(&mut self, key: usize) -> T {
+        self.try_remove(key).expect("invalid key")
+    }
+
+    #[inline]
+    fn unlink(&mut self, key: usize) {
+        self.linear &= self.tail == Some(key) && key + 1 == self.slab.len();
+        let item = self.slab.get_mut(key).unwrap();
+        let prev = item.prev.take();
+        let next = item.next.take();
 
         match (prev, next) {
             (Some(prev), Some(next)) => {
@@ -190,20 +527,429 @@ impl<T> SlabLinkedList<T> {
                 assert_eq!(self.tail.take(), Some(key));
             }
         }
+    }
 
-        Some(value)
+    #[inline]
+    pub fn clear(&mut self) {
+        self.slab.clear();
+        self.head = None;
+        self.tail = None;
+        self.linear = true;
+    }
+
+    /// Removes every element in list order. The list is empty afterwards even if the
+    /// iterator is dropped early.
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain::new(self)
+    }
+
+    /// Removes and yields, in list order, the elements for which `pred` returns `true`.
+    /// Elements not visited before the iterator is dropped are kept.
+    #[inline]
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(usize, &mut T) -> bool,
+    {
+        ExtractIf::new(self, pred)
+    }
+
+    /// Keeps only the elements for which `f` returns `true`, visiting them in list order.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut T) -> bool,
+    {
+        let mut cursor = self.head;
+        while let Some(key) = cursor {
+            let item = unsafe { self.slab.get_unchecked_mut(key) };
+            cursor = item.next;
+            if !f(key, item.value.get_mut()) {
+                self.remove(key);
+            }
+        }
     }
 
     #[inline]
     #[track_caller]
-    pub fn remove(&mut self, key: usize) -> T {
-        self.try_remove(key).expect("invalid key")
+    pub fn move_to_front(&mut self, key: usize) {
+        if let Err(error) = self.try_move_to_front(key) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn move_to_back(&mut self, key: usize) {
+        if let Err(error) = self.try_move_to_back(key) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn move_before(&mut self, key: usize, target_key: usize) {
+        if let Err(error) = self.try_move_before(key, target_key) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn move_after(&mut self, key: usize, target_key: usize) {
+        if let Err(error) = self.try_move_after(key, target_key) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn swap(&mut self, key1: usize, key2: usize) {
+        if let Err(error) = self.try_swap(key1, key2) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    pub fn try_move_to_front(&mut self, key: usize) -> Result<(), Error> {
+        if !self.slab.contains(key) {
+            return Err(Error::InvalidKey);
+        }
+        match self.head {
+            Some(head) if head != key => {
+                self.unlink(key);
+                self.link_before(key, head);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn try_move_to_back(&mut self, key: usize) -> Result<(), Error> {
+        if !self.slab.contains(key) {
+            return Err(Error::InvalidKey);
+        }
+        match self.tail {
+            Some(tail) if tail != key => {
+                self.unlink(key);
+                self.link_after(key, tail);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn try_move_before(&mut self, key: usize, target_key: usize) -> Result<(), Error> {
+        if key == target_key {
+            return Err(Error::SameKey);
+        }
+        if !self.slab.contains(key) || !self.slab.contains(target_key) {
+            return Err(Error::InvalidKey);
+        }
+        if self.next_key(key) != Some(target_key) {
+            self.unlink(key);
+            self.link_before(key, target_key);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn try_move_after(&mut self, key: usize, target_key: usize) -> Result<(), Error> {
+        if key == target_key {
+            return Err(Error::SameKey);
+        }
+        if !self.slab.contains(key) || !self.slab.contains(target_key) {
+            return Err(Error::InvalidKey);
+        }
+        if self.prev_key(key) != Some(target_key) {
+            self.unlink(key);
+            self.link_after(key, target_key);
+        }
+        Ok(())
+    }
+
+    /// Exchanges the positions of two elements. Both keep their keys.
+    pub fn try_swap(&mut self, key1: usize, key2: usize) -> Result<(), Error> {
+        if !self.slab.contains(key1) || !self.slab.contains(key2) {
+            return Err(Error::InvalidKey);
+        }
+        if key1 == key2 {
+            return Ok(());
+        }
+        let next1 = self.next_key(key1);
+        if next1 == Some(key2) {
+            self.unlink(key1);
+            self.link_after(key1, key2);
+        } else if self.next_key(key2) == Some(key1) {
+            self.unlink(key2);
+            self.link_after(key2, key1);
+        } else {
+            self.unlink(key1);
+            self.link_before(key1, key2);
+            self.unlink(key2);
+            match next1 {
+                Some(next1) => self.link_before(key2, next1),
+                None => self.link_after(key2, self.tail.unwrap()),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> SlabLinkedList<T> {
+    /// Shows every slot of the slab in storage order with its links, flagging `head`,
+    /// `tail` and vacant slots. Vacant slots after the last occupied one are not shown, as
+    /// `Slab` does not expose how many there are.
+    #[inline]
+    pub fn debug_links(&self) -> DebugLinks<'_, T> {
+        DebugLinks { list: self }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SlabLinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+pub struct DebugLinks<'a, T> {
+    list: &'a SlabLinkedList<T>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for DebugLinks<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Link(Option<usize>);
+
+        impl fmt::Display for Link {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.0 {
+                    None => f.write_str("-"),
+                    Some(key) => key.fmt(f),
+                }
+            }
+        }
+
+        let list = self.list;
+        let mut expected = 0;
+        for (key, item) in list.slab.iter() {
+            for vacant in expected..key {
+                writeln!(f, "{}: vacant", vacant)?;
+            }
+            expected = key + 1;
+            write!(
+                f,
+                "{}: {:?} ({} -> {})",
+                key,
+                item.value.get(),
+                Link(item.prev),
+                Link(item.next)
+            )?;
+            if list.head == Some(key) {
+                f.write_str(" head")?;
+            }
+            if list.tail == Some(key) {
+                f.write_str(" tail")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: PartialEq> SlabLinkedList<T> {
+    /// Like `==`, but also requires every element to have the same key in both lists.
+    #[inline]
+    pub fn structurally_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter_with_keys().eq(other.iter_with_keys())
+    }
+}
+
+impl<T: PartialEq> PartialEq for SlabLinkedList<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for SlabLinkedList<T> {}
+
+impl<T: Hash> Hash for SlabLinkedList<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for value in self {
+            value.hash(state);
+        }
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for SlabLinkedList<T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for SlabLinkedList<T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T> FromIterator<T> for SlabLinkedList<T> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for SlabLinkedList<T> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.extend_back(iter, |_| {});
+    }
+}
+
+impl<'a, T: Copy + 'a> Extend<&'a T> for SlabLinkedList<T> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend_back(iter.into_iter().copied(), |_| {});
+    }
+}
+
+impl<T> From<Vec<T>> for SlabLinkedList<T> {
+    #[inline]
+    fn from(values: Vec<T>) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for SlabLinkedList<T> {
+    #[inline]
+    fn from(values: [T; N]) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl<T> Index<usize> for SlabLinkedList<T> {
+    type Output = T;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, key: usize) -> &Self::Output {
+        self.get(key).expect("invalid key")
+    }
+}
+
+impl<T> IndexMut<usize> for SlabLinkedList<T> {
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, key: usize) -> &mut Self::Output {
+        self.get_mut(key).expect("invalid key")
+    }
+}
+
+impl<T> SlabLinkedList<T> {
+    /// Moves elements into the vacant slots at the front of the slab and releases the
+    /// unused capacity. `rekey` is called with `(old_key, new_key)` for every element
+    /// whose key changed; all other keys stay valid.
+    pub fn compact<F>(&mut self, mut rekey: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        let mut moves = Vec::new();
+        self.slab.compact(|item, from, to| {
+            item.key.replace(to);
+            moves.push((from, to));
+            true
+        });
+
+        let Some(max) = moves.iter().map(|&(from, _)| from).max() else {
+            return;
+        };
+        let mut remap = (0..=max).collect::<Vec<_>>();
+        for &(from, to) in &moves {
+            remap[from] = to;
+        }
+        let remap = |key: usize| remap.get(key).copied().unwrap_or(key);
+
+        for (_, item) in self.slab.iter_mut() {
+            item.prev = item.prev.map(remap);
+            item.next = item.next.map(remap);
+        }
+        self.head = self.head.map(remap);
+        self.tail = self.tail.map(remap);
+
+        for (from, to) in moves {
+            rekey(from, to);
+        }
+    }
+
+    /// Rebuilds the slab so that keys are `0..len` in list order, which makes iteration
+    /// walk memory sequentially. Returns a table mapping every old key to its new key.
+    pub fn relinearize(&mut self) -> Vec<Option<usize>> {
+        let len = self.len();
+        let mut old = std::mem::replace(&mut self.slab, Slab::with_capacity(len));
+        let mut remap = Vec::new();
+        let mut cursor = self.head;
+        while let Some(old_key) = cursor {
+            let mut item = old.remove(old_key);
+            cursor = item.next;
+            let key = self.slab.vacant_key();
+            item.key.replace(key);
+            item.prev = key.checked_sub(1);
+            item.next = Some(key + 1).filter(|&next| next < len);
+            self.slab.insert(item);
+            if remap.len() <= old_key {
+                remap.resize(old_key + 1, None);
+            }
+            remap[old_key] = Some(key);
+        }
+        debug_assert!(old.is_empty());
+        self.head = len.checked_sub(1).map(|_| 0);
+        self.tail = len.checked_sub(1);
+        self.linear = true;
+        remap
+    }
+
+    /// Whether keys are `0..len` in list order, as after [`relinearize`](Self::relinearize).
+    /// Iterators over a linear list skip reading the links.
+    #[inline]
+    pub fn is_linear(&self) -> bool {
+        self.linear
+    }
+
+    /// Walks the whole list and checks that every link, `head` and `tail` agree with each other.
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut prev = None;
+        let mut cursor = self.head;
+        let mut count = 0;
+        while let Some(key) = cursor {
+            let item = self.slab.get(key).ok_or(Error::CorruptLinks)?;
+            if item.key != Some(key) || item.prev != prev || count == self.slab.len() {
+                return Err(Error::CorruptLinks);
+            }
+            count += 1;
+            prev = cursor;
+            cursor = item.next;
+        }
+        if prev != self.tail || count != self.slab.len() {
+            return Err(Error::CorruptLinks);
+        }
+        if self.linear && self.keys().enumerate().any(|(i, key)| i != key) {
+            return Err(Error::CorruptLinks);
+        }
+        Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Item<T> {
-    value: T,
+    value: ValueCell<T>,
     key: Option<usize>,
     prev: Option<usize>,
     next: Option<usize>,
@@ -213,7 +959,7 @@ impl<T> From<T> for Item<T> {
     #[inline]
     fn from(value: T) -> Self {
         Self {
-            value,
+            value: ValueCell::new(value),
             key: None,
             prev: None,
             next: None,
@@ -279,6 +1025,387 @@ mod tests {
         assert_eq!(list.slab.len(), 0);
     }
 
+    #[test]
+    fn get_mut() {
+        let mut list = SlabLinkedList::new();
+        let i1 = list.push_back(1);
+        let i2 = list.push_back(2);
+        *list.get_mut(i1).unwrap() += 10;
+        *list.front_mut().unwrap() += 100;
+        *list.back_mut().unwrap() += 20;
+        list[i2] += 1;
+        assert_eq!(list[i1], 111);
+        assert_eq!(list[i2], 23);
+
+        let (v1, v2) = list.get2_mut(i1, i2).unwrap();
+        std::mem::swap(v1, v2);
+        assert_eq!(list.front(), Some(&23));
+        assert_eq!(list.back(), Some(&111));
+        assert!(list.get_mut(i2 + 1).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid key")]
+    fn index_invalid_key() {
+        let mut list = SlabLinkedList::new();
+        let i1 = list.push_back(1);
+        list.remove(i1);
+        let _ = list[i1];
+    }
+
+    #[test]
+    fn navigation() {
+        let mut list = SlabLinkedList::new();
+        assert_eq!(list.front_key(), None);
+        assert_eq!(list.back_key(), None);
+        let i1 = list.push_back("a");
+        let i2 = list.push_back("b");
+        let i3 = list.push_back("c");
+        assert_eq!(list.front_key(), Some(i1));
+        assert_eq!(list.back_key(), Some(i3));
+        assert_eq!(list.next_key(i1), Some(i2));
+        assert_eq!(list.next_key(i3), None);
+        assert_eq!(list.prev_key(i2), Some(i1));
+        assert_eq!(list.prev_key(i1), None);
+        assert!(list.is_front(i1));
+        assert!(!list.is_front(i2));
+        assert!(list.is_back(i3));
+
+        list.remove(i2);
+        assert!(!list.contains_key(i2));
+        assert!(list.contains_key(i3));
+        assert_eq!(list.next_key(i1), Some(i3));
+        assert_eq!(list.next_key(i2), None);
+        assert_eq!(list.prev_key(i3), Some(i1));
+    }
+
+    #[test]
+    fn try_insert() {
+        let mut list = SlabLinkedList::new();
+        let i1 = list.push_back("a");
+        let i2 = list.try_insert_after("c", i1).unwrap();
+        let i3 = list.try_insert_before("b", i2).unwrap();
+        assert_eq!(list.keys().collect::<Vec<_>>(), [i1, i3, i2]);
+
+        let invalid = 100;
+        let err = list.try_insert_before("x", invalid).unwrap_err();
+        assert_eq!(err.into_inner(), "x");
+        let err = list.try_insert_after("y", invalid).unwrap_err();
+        assert_eq!(err.value(), &"y");
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.slab.len(), 3);
+    }
+
+    #[test]
+    fn insert_invalid_key_leaves_no_orphan() {
+        let mut list = SlabLinkedList::new();
+        list.push_back("a");
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.insert_after("b", 7);
+        }));
+        assert!(result.is_err());
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn errors() {
+        let mut list = SlabLinkedList::new();
+        let i1 = list.push_back("a");
+        let i2 = list.push_back("b");
+        assert!(list.get2_mut(i1, i1).is_none());
+        assert_eq!(list.try_get2_mut(i1, i1), Err(Error::SameKey));
+        assert_eq!(list.try_get2_mut(i1, 100), Err(Error::InvalidKey));
+        assert!(list.try_get2_mut(i1, i2).is_ok());
+        assert_eq!(list.try_remove(i1), Ok("a"));
+        assert_eq!(list.try_remove(i1), Err(Error::InvalidKey));
+        assert_eq!(
+            list.try_insert_after("c", i1).unwrap_err().error(),
+            Error::InvalidKey
+        );
+        assert_eq!(Error::InvalidKey.to_string(), "invalid key");
+    }
+
+    #[test]
+    fn validate() {
+        let mut list = SlabLinkedList::new();
+        assert_eq!(list.validate(), Ok(()));
+        let i1 = list.push_back("a");
+        let i2 = list.push_back("b");
+        list.push_back("c");
+        assert_eq!(list.validate(), Ok(()));
+
+        list.slab[i2].next = Some(i1);
+        assert_eq!(list.validate(), Err(Error::CorruptLinks));
+        list.slab[i2].next = None;
+        assert_eq!(list.validate(), Err(Error::CorruptLinks));
+    }
+
+    #[test]
+    fn move_items() {
+        let mut list = SlabLinkedList::new();
+        let k = (0..5).map(|v| list.push_back(v)).collect::<Vec<_>>();
+        let values = |list: &SlabLinkedList<i32>| list.iter().copied().collect::<Vec<_>>();
+
+        list.move_to_front(k[3]);
+        assert_eq!(values(&list), [3, 0, 1, 2, 4]);
+        list.move_to_front(k[3]);
+        assert_eq!(values(&list), [3, 0, 1, 2, 4]);
+        list.move_to_back(k[0]);
+        assert_eq!(values(&list), [3, 1, 2, 4, 0]);
+        list.move_to_back(k[3]);
+        assert_eq!(values(&list), [1, 2, 4, 0, 3]);
+        list.move_before(k[0], k[1]);
+        assert_eq!(values(&list), [0, 1, 2, 4, 3]);
+        list.move_after(k[4], k[3]);
+        assert_eq!(values(&list), [0, 1, 2, 3, 4]);
+        list.move_after(k[1], k[0]);
+        assert_eq!(values(&list), [0, 1, 2, 3, 4]);
+        assert_eq!(list.keys().collect::<Vec<_>>(), k);
+        assert_eq!(list.validate(), Ok(()));
+
+        assert_eq!(list.try_move_before(k[1], k[1]), Err(Error::SameKey));
+        assert_eq!(list.try_move_after(k[1], 100), Err(Error::InvalidKey));
+        assert_eq!(list.try_move_to_front(100), Err(Error::InvalidKey));
+    }
+
+    #[test]
+    fn swap() {
+        let mut list = SlabLinkedList::new();
+        let k = (0..5).map(|v| list.push_back(v)).collect::<Vec<_>>();
+        let values = |list: &SlabLinkedList<i32>| list.iter().copied().collect::<Vec<_>>();
+
+        list.swap(k[0], k[4]);
+        assert_eq!(values(&list), [4, 1, 2, 3, 0]);
+        list.swap(k[1], k[2]);
+        assert_eq!(values(&list), [4, 2, 1, 3, 0]);
+        list.swap(k[1], k[2]);
+        assert_eq!(values(&list), [4, 1, 2, 3, 0]);
+        list.swap(k[3], k[1]);
+        assert_eq!(values(&list), [4, 3, 2, 1, 0]);
+        list.swap(k[2], k[2]);
+        assert_eq!(values(&list), [4, 3, 2, 1, 0]);
+        assert_eq!(list.validate(), Ok(()));
+        assert_eq!(list[k[4]], 4);
+        assert_eq!(list.try_swap(k[0], 100), Err(Error::InvalidKey));
+    }
+
+    #[test]
+    fn capacity() {
+        let mut list = SlabLinkedList::with_capacity(8);
+        assert!(list.capacity() >= 8);
+        let capacity = list.capacity();
+        for i in 0..capacity {
+            list.push_back(i);
+        }
+        assert_eq!(list.capacity(), capacity);
+
+        list.reserve(10);
+        assert!(list.capacity() >= capacity + 10);
+        list.reserve_exact(20);
+        assert!(list.capacity() >= capacity + 20);
+        assert_eq!(list.try_reserve(30), Ok(()));
+        assert!(list.capacity() >= capacity + 30);
+        assert_eq!(list.try_reserve(usize::MAX), Err(Error::CapacityExceeded));
+
+        while list.len() > 2 {
+            list.pop_back();
+        }
+        list.shrink_to_fit();
+        assert!(list.capacity() < capacity + 30);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [0, 1]);
+    }
+
+    #[test]
+    fn compact() {
+        let mut list = SlabLinkedList::new();
+        let mut keys = (0..10).map(|v| list.push_back(v)).collect::<Vec<_>>();
+        for i in [0, 2, 3, 7] {
+            list.remove(keys[i]);
+        }
+        let capacity = list.capacity();
+
+        let mut moves = Vec::new();
+        list.compact(|from, to| moves.push((from, to)));
+        assert!(!moves.is_empty());
+        assert!(list.capacity() < capacity);
+        for (from, to) in moves {
+            let i = keys.iter().position(|&key| key == from).unwrap();
+            keys[i] = to;
+        }
+        for i in [1, 4, 5, 6, 8, 9] {
+            assert_eq!(list[keys[i]], i);
+            assert!(keys[i] < list.len());
+        }
+        assert_eq!(list.validate(), Ok(()));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 4, 5, 6, 8, 9]);
+        assert_eq!(list.front_key(), Some(keys[1]));
+        assert_eq!(list.back_key(), Some(keys[9]));
+    }
+
+    #[test]
+    fn relinearize() {
+        let mut list = SlabLinkedList::new();
+        let i0 = list.push_back(0);
+        let i1 = list.push_back(1);
+        assert!(list.is_linear());
+        let i2 = list.push_front(2);
+        let i3 = list.insert_after(3, i0);
+        list.remove(i1);
+        assert!(!list.is_linear());
+        // 2, 0, 3
+
+        let remap = list.relinearize();
+        assert!(list.is_linear());
+        assert_eq!(remap[i2], Some(0));
+        assert_eq!(remap[i0], Some(1));
+        assert_eq!(remap[i3], Some(2));
+        assert_eq!(remap.get(i1).copied().flatten(), None);
+        assert_eq!(list.keys().collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(list.keys().rev().collect::<Vec<_>>(), [2, 1, 0]);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [2, 0, 3]);
+        assert_eq!(list.validate(), Ok(()));
+
+        list.push_back(4);
+        list.pop_back();
+        list.pop_back();
+        assert!(list.is_linear());
+        list.push_back(5);
+        assert!(list.is_linear());
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [2, 0, 5]);
+        assert_eq!(list.validate(), Ok(()));
+        list.move_to_front(2);
+        assert!(!list.is_linear());
+        assert_eq!(list.validate(), Ok(()));
+
+        let mut empty = SlabLinkedList::<()>::new();
+        assert!(empty.relinearize().is_empty());
+        assert_eq!(empty.front_key(), None);
+    }
+
+    #[test]
+    fn clear_and_retain() {
+        let mut list = SlabLinkedList::new();
+        let keys = (0..6).map(|v| list.push_back(v)).collect::<Vec<_>>();
+        list.move_to_front(keys[5]);
+        let mut visited = Vec::new();
+        list.retain(|key, value| {
+            visited.push(key);
+            *value *= 10;
+            *value % 20 == 0
+        });
+        assert_eq!(visited, [5, 0, 1, 2, 3, 4].map(|i| keys[i]));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [0, 20, 40]);
+        assert_eq!(list.validate(), Ok(()));
+
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(list.front_key(), None);
+        assert_eq!(list.back_key(), None);
+        list.push_back(1);
+        assert_eq!(list.validate(), Ok(()));
+    }
+
+    #[test]
+    fn from_iter_and_extend() {
+        let mut list = (0..3).collect::<SlabLinkedList<_>>();
+        assert!(list.is_linear());
+        list.extend(vec![3, 4]);
+        list.extend(&[5, 6]);
+        assert!(list.is_linear());
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(list.validate(), Ok(()));
+
+        list.remove(2);
+        list.remove(0);
+        let keys = list.extend_returning_keys([7, 8, 9]);
+        assert_eq!(keys.len(), 3);
+        assert_eq!(
+            keys.iter().map(|&key| list[key]).collect::<Vec<_>>(),
+            [7, 8, 9]
+        );
+        assert_eq!(list.keys().skip(5).collect::<Vec<_>>(), keys);
+        assert!(!list.is_linear());
+        assert_eq!(list.validate(), Ok(()));
+
+        let list = SlabLinkedList::from(vec!["a", "b"]);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), ["a", "b"]);
+        let mut list = SlabLinkedList::from(["a", "b"]);
+        assert_eq!(list.pop_back(), Some("b"));
+        assert_eq!(list.validate(), Ok(()));
+
+        let mut list = SlabLinkedList::new();
+        list.push_front(1);
+        list.push_front(0);
+        list.clear();
+        list.extend([1, 2]);
+        assert_eq!(list.validate(), Ok(()));
+    }
+
+    #[test]
+    fn clone_and_compare() {
+        let mut list = SlabLinkedList::new();
+        let i1 = list.push_back(1);
+        let i2 = list.push_front(2);
+        list.push_back(3);
+        list.remove(i1);
+
+        let mut cloned = list.clone();
+        assert!(cloned.structurally_eq(&list));
+        assert_eq!(cloned[i2], 2);
+        assert_eq!(cloned.push_back(4), list.push_back(4));
+
+        let other = SlabLinkedList::from([2, 3, 4]);
+        assert_eq!(other, list);
+        assert!(!other.structurally_eq(&list));
+        assert_ne!(SlabLinkedList::from([2, 3]), list);
+        assert!(SlabLinkedList::from([2, 3]) < list);
+        assert!(SlabLinkedList::from([2, 4]) > list);
+        assert_eq!(
+            SlabLinkedList::from([1, 2]).cmp(&SlabLinkedList::from([1, 2])),
+            Ordering::Equal
+        );
+
+        let hash = |list: &SlabLinkedList<i32>| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            list.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&other), hash(&list));
+    }
+
+    #[test]
+    fn debug() {
+        let mut list = SlabLinkedList::new();
+        let i0 = list.push_back("a");
+        list.push_back("b");
+        list.push_front("c");
+        list.push_back("d");
+        list.remove(i0);
+        assert_eq!(format!("{:?}", list), r#"["c", "b", "d"]"#);
+        assert_eq!(
+            format!("{:?}", list.debug_links()),
+            concat!(
+                "0: vacant\n",
+                "1: \"b\" (2 -> 3)\n",
+                "2: \"c\" (- -> 1) head\n",
+                "3: \"d\" (1 -> -) tail\n",
+            )
+        );
+        list.pop_back();
+        assert_eq!(
+            format!("{:?}", list.debug_links()),
+            concat!(
+                "0: vacant\n",
+                "1: \"b\" (2 -> -) tail\n",
+                "2: \"c\" (- -> 1) head\n",
+            )
+        );
+        assert_eq!(format!("{:?}", SlabLinkedList::<()>::new()), "[]");
+    }
+
     #[test]
     fn pop_front() {
         let mut list = SlabLinkedList::new();
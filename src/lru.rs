@@ -0,0 +1,384 @@
+use crate::index::HashIndex;
+use crate::{Iter, IterMut, SlabLinkedList};
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+
+/// A least-recently-used cache. Entries live in a [`SlabLinkedList`] ordered from most to
+/// least recently used, and a hit relinks the entry in place instead of reinserting it.
+#[derive(Debug, Clone)]
+pub struct LruCache<K, V, S = RandomState> {
+    list: SlabLinkedList<(K, V)>,
+    index: HashIndex<S>,
+    cap: usize,
+}
+
+impl<K: Hash + Eq, V> LruCache<K, V> {
+    #[inline]
+    pub fn new(cap: usize) -> Self {
+        Self::with_hasher(cap, Default::default())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> LruCache<K, V, S> {
+    #[inline]
+    pub fn with_hasher(cap: usize, hash_builder: S) -> Self {
+        Self {
+            list: SlabLinkedList::with_capacity(cap),
+            index: HashIndex::with_capacity_and_hasher(cap, hash_builder),
+            cap,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    #[inline]
+    pub fn cap(&self) -> usize {
+        self.cap
+    }
+
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        self.index.hasher()
+    }
+
+    #[inline]
+    fn find<Q>(&self, k: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.index.hash(k);
+        self.index.find(hash, |key| self.list[key].0.borrow() == k)
+    }
+
+    #[inline]
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(k).is_some()
+    }
+
+    /// Returns the value and marks the entry as most recently used.
+    #[inline]
+    pub fn get<Q>(&mut self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let key = self.find(k)?;
+        self.list.move_to_front(key);
+        Some(&self.list[key].1)
+    }
+
+    /// Returns the value and marks the entry as most recently used.
+    #[inline]
+    pub fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let key = self.find(k)?;
+        self.list.move_to_front(key);
+        Some(&mut self.list[key].1)
+    }
+
+    /// Returns the value without changing the recency order.
+    #[inline]
+    pub fn peek<Q>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let key = self.find(k)?;
+        Some(&self.list[key].1)
+    }
+
+    /// Returns the value without changing the recency order.
+    #[inline]
+    pub fn peek_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let key = self.find(k)?;
+        Some(&mut self.list[key].1)
+    }
+
+    #[inline]
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        self.list.back().map(|(k, v)| (k, v))
+    }
+
+    /// Inserts an entry as the most recently used one.
+    ///
+    /// Returns the replaced entry if `k` was already present, otherwise the least recently
+    /// used entry if it had to be evicted to stay within the capacity.
+    pub fn put(&mut self, k: K, v: V) -> Option<(K, V)> {
+        let hash = self.index.hash(&k);
+        if let Some(key) = self.index.find(hash, |key| self.list[key].0 == k) {
+            self.list.move_to_front(key);
+            let entry = std::mem::replace(&mut self.list[key], (k, v));
+            return Some(entry);
+        }
+        if self.cap == 0 {
+            return Some((k, v));
+        }
+        let evicted = if self.list.len() >= self.cap {
+            self.pop_lru()
+        } else {
+            None
+        };
+        let key = self.list.push_front((k, v));
+        self.index.insert(hash, key);
+        evicted
+    }
+
+    #[inline]
+    pub fn pop<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.index.hash(k);
+        let key = self
+            .index
+            .find(hash, |key| self.list[key].0.borrow() == k)?;
+        self.index.remove(hash, key);
+        Some(self.list.remove(key).1)
+    }
+
+    #[inline]
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let key = self.list.back_key()?;
+        let hash = self.index.hash(&self.list[key].0);
+        self.index.remove(hash, key);
+        self.list.pop_back()
+    }
+
+    /// Changes the capacity, evicting least recently used entries if it shrinks.
+    pub fn resize(&mut self, cap: usize) {
+        while self.list.len() > cap {
+            self.pop_lru();
+        }
+        self.cap = cap;
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.index.clear();
+    }
+}
+
+impl<K, V, S> LruCache<K, V, S> {
+    /// Iterates from the most to the least recently used entry.
+    #[inline]
+    pub fn iter(&self) -> LruIter<'_, K, V> {
+        LruIter {
+            inner: self.list.iter(),
+        }
+    }
+
+    /// Iterates from the most to the least recently used entry.
+    #[inline]
+    pub fn iter_mut(&mut self) -> LruIterMut<'_, K, V> {
+        LruIterMut {
+            inner: self.list.iter_mut(),
+        }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a LruCache<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = LruIter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut LruCache<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = LruIterMut<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[derive(Debug)]
+pub struct LruIter<'a, K, V> {
+    inner: Iter<'a, (K, V)>,
+}
+
+impl<K, V> Clone for LruIter<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for LruIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| (k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for LruIter<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, v)| (k, v))
+    }
+}
+
+impl<K, V> ExactSizeIterator for LruIter<'_, K, V> {}
+
+impl<K, V> FusedIterator for LruIter<'_, K, V> {}
+
+#[derive(Debug)]
+pub struct LruIterMut<'a, K, V> {
+    inner: IterMut<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for LruIterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| (&*k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for LruIterMut<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, v)| (&*k, v))
+    }
+}
+
+impl<K, V> ExactSizeIterator for LruIterMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for LruIterMut<'_, K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, Hasher};
+
+    fn keys<K: Copy, V, S>(cache: &LruCache<K, V, S>) -> Vec<K> {
+        cache.iter().map(|(k, _)| *k).collect()
+    }
+
+    #[test]
+    fn put_and_get() {
+        let mut cache = LruCache::new(2);
+        assert_eq!(cache.put("a", 1), None);
+        assert_eq!(cache.put("b", 2), None);
+        assert_eq!(keys(&cache), ["b", "a"]);
+
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(keys(&cache), ["a", "b"]);
+        assert_eq!(cache.peek("b"), Some(&2));
+        assert_eq!(keys(&cache), ["a", "b"]);
+        assert_eq!(cache.peek_lru(), Some((&"b", &2)));
+
+        assert_eq!(cache.put("c", 3), Some(("b", 2)));
+        assert_eq!(keys(&cache), ["c", "a"]);
+        assert_eq!(cache.put("a", 10), Some(("a", 1)));
+        assert_eq!(keys(&cache), ["a", "c"]);
+        *cache.get_mut("c").unwrap() += 1;
+        assert_eq!(
+            cache.iter().rev().collect::<Vec<_>>(),
+            [(&"a", &10), (&"c", &4)]
+        );
+        assert!(!cache.contains_key("b"));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn pop_and_resize() {
+        let mut cache = LruCache::new(4);
+        for (i, k) in ["a", "b", "c", "d"].into_iter().enumerate() {
+            cache.put(k.to_string(), i);
+        }
+        assert_eq!(cache.pop("b"), Some(1));
+        assert_eq!(cache.pop("b"), None);
+        assert_eq!(cache.pop_lru(), Some(("a".to_string(), 0)));
+        cache.put("e".to_string(), 4);
+        cache.get("c");
+        cache.resize(1);
+        assert_eq!(cache.cap(), 1);
+        assert_eq!(keys_owned(&cache), ["c"]);
+        for (_, v) in &mut cache {
+            *v *= 10;
+        }
+        assert_eq!(cache.peek("c"), Some(&20));
+
+        cache.resize(0);
+        assert!(cache.is_empty());
+        assert_eq!(cache.put("f".to_string(), 5), Some(("f".to_string(), 5)));
+        cache.resize(2);
+        cache.put("g".to_string(), 6);
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.get("g"), None);
+    }
+
+    fn keys_owned<V, S>(cache: &LruCache<String, V, S>) -> Vec<&str> {
+        cache.iter().map(|(k, _)| k.as_str()).collect()
+    }
+
+    #[derive(Default)]
+    struct ConstantHasher;
+
+    impl Hasher for ConstantHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, _: &[u8]) {}
+    }
+
+    #[test]
+    fn colliding_hasher() {
+        let mut cache = LruCache::with_hasher(3, BuildHasherDefault::<ConstantHasher>::default());
+        cache.put(1, "a");
+        cache.put(2, "b");
+        cache.put(3, "c");
+        assert_eq!(cache.get(&1), Some(&"a"));
+        assert_eq!(cache.put(4, "d"), Some((2, "b")));
+        assert_eq!(cache.pop(&3), Some("c"));
+        assert_eq!(cache.peek(&4), Some(&"d"));
+        assert_eq!(cache.peek(&2), None);
+        assert_eq!(keys(&cache), [4, 1]);
+    }
+}
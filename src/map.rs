@@ -0,0 +1,826 @@
+use crate::index::HashIndex;
+use crate::{IntoIter, Iter, IterMut, SlabLinkedList};
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::ops::Index;
+
+/// A hash map that remembers insertion order. Entries live in a [`SlabLinkedList`], so
+/// removing or reordering an entry is O(1) and keys are stored only once.
+#[derive(Clone)]
+pub struct OrderedMap<K, V, S = RandomState> {
+    list: SlabLinkedList<(K, V)>,
+    index: HashIndex<S>,
+}
+
+impl<K, V> OrderedMap<K, V> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_hasher(Default::default())
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<K, V, S> OrderedMap<K, V, S> {
+    #[inline]
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            list: SlabLinkedList::with_capacity(capacity),
+            index: HashIndex::with_capacity_and_hasher(capacity, hash_builder),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        self.index.hasher()
+    }
+
+    #[inline]
+    pub fn front(&self) -> Option<(&K, &V)> {
+        self.list.front().map(|(k, v)| (k, v))
+    }
+
+    #[inline]
+    pub fn back(&self) -> Option<(&K, &V)> {
+        self.list.back().map(|(k, v)| (k, v))
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.index.clear();
+    }
+
+    #[inline]
+    pub fn iter(&self) -> OrderedMapIter<'_, K, V> {
+        OrderedMapIter {
+            inner: self.list.iter(),
+        }
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> OrderedMapIterMut<'_, K, V> {
+        OrderedMapIterMut {
+            inner: self.list.iter_mut(),
+        }
+    }
+
+    #[inline]
+    pub fn keys(&self) -> OrderedMapKeys<'_, K, V> {
+        OrderedMapKeys { inner: self.iter() }
+    }
+
+    #[inline]
+    pub fn values(&self) -> OrderedMapValues<'_, K, V> {
+        OrderedMapValues { inner: self.iter() }
+    }
+
+    #[inline]
+    pub fn values_mut(&mut self) -> OrderedMapValuesMut<'_, K, V> {
+        OrderedMapValuesMut {
+            inner: self.iter_mut(),
+        }
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> OrderedMap<K, V, S> {
+    #[inline]
+    fn find<Q>(&self, k: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.index.hash(k);
+        self.index.find(hash, |key| self.list[key].0.borrow() == k)
+    }
+
+    #[inline]
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(k).is_some()
+    }
+
+    #[inline]
+    pub fn get<Q>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let key = self.find(k)?;
+        Some(&self.list[key].1)
+    }
+
+    #[inline]
+    pub fn get_key_value<Q>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let key = self.find(k)?;
+        let (k, v) = &self.list[key];
+        Some((k, v))
+    }
+
+    #[inline]
+    pub fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let key = self.find(k)?;
+        Some(&mut self.list[key].1)
+    }
+
+    /// Inserts at the back, or replaces the value in place if `k` is already present.
+    #[inline]
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
+        match self.entry(k) {
+            Entry::Occupied(mut entry) => Some(entry.insert(v)),
+            Entry::Vacant(entry) => {
+                entry.insert(v);
+                None
+            }
+        }
+    }
+
+    #[inline]
+    pub fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(k).map(|(_, v)| v)
+    }
+
+    #[inline]
+    pub fn remove_entry<Q>(&mut self, k: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.index.hash(k);
+        let key = self
+            .index
+            .find(hash, |key| self.list[key].0.borrow() == k)?;
+        self.index.remove(hash, key);
+        Some(self.list.remove(key))
+    }
+
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<(K, V)> {
+        let key = self.list.front_key()?;
+        self.unindex(key);
+        self.list.pop_front()
+    }
+
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<(K, V)> {
+        let key = self.list.back_key()?;
+        self.unindex(key);
+        self.list.pop_back()
+    }
+
+    #[inline]
+    fn unindex(&mut self, key: usize) {
+        let hash = self.index.hash(&self.list[key].0);
+        self.index.remove(hash, key);
+    }
+
+    /// Moves the entry for `k` to the front. Returns `false` if `k` is not present.
+    #[inline]
+    pub fn move_to_front<Q>(&mut self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let Some(key) = self.find(k) else {
+            return false;
+        };
+        self.list.move_to_front(key);
+        true
+    }
+
+    /// Moves the entry for `k` to the back. Returns `false` if `k` is not present.
+    #[inline]
+    pub fn move_to_back<Q>(&mut self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let Some(key) = self.find(k) else {
+            return false;
+        };
+        self.list.move_to_back(key);
+        true
+    }
+
+    #[inline]
+    pub fn entry(&mut self, k: K) -> Entry<'_, K, V, S> {
+        let hash = self.index.hash(&k);
+        match self.index.find(hash, |key| self.list[key].0 == k) {
+            Some(key) => Entry::Occupied(OccupiedEntry {
+                map: self,
+                hash,
+                key,
+            }),
+            None => Entry::Vacant(VacantEntry { map: self, hash, k }),
+        }
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let index = &mut self.index;
+        self.list.retain(|key, (k, v)| {
+            let keep = f(k, v);
+            if !keep {
+                index.remove(index.hash(k), key);
+            }
+            keep
+        });
+    }
+}
+
+impl<K, V, S: Default> Default for OrderedMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_hasher(Default::default())
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for OrderedMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+// Two maps are equal if they hold equal entries in the same order.
+impl<K: PartialEq, V: PartialEq, S> PartialEq for OrderedMap<K, V, S> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.list == other.list
+    }
+}
+
+impl<K: Eq, V: Eq, S> Eq for OrderedMap<K, V, S> {}
+
+impl<K, Q, V, S> Index<&Q> for OrderedMap<K, V, S>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, k: &Q) -> &V {
+        self.get(k).expect("key not found")
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher + Default> FromIterator<(K, V)> for OrderedMap<K, V, S> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> Extend<(K, V)> for OrderedMap<K, V, S> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K: Hash + Eq, V, const N: usize> From<[(K, V); N]> for OrderedMap<K, V> {
+    #[inline]
+    fn from(entries: [(K, V); N]) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+pub enum Entry<'a, K, V, S> {
+    Occupied(OccupiedEntry<'a, K, V, S>),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+impl<'a, K: Hash + Eq, V, S: BuildHasher> Entry<'a, K, V, S> {
+    #[inline]
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    #[inline]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    #[inline]
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    #[inline]
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for Entry<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Occupied(entry) => f.debug_tuple("Occupied").field(entry).finish(),
+            Entry::Vacant(entry) => f.debug_tuple("Vacant").field(entry).finish(),
+        }
+    }
+}
+
+pub struct OccupiedEntry<'a, K, V, S> {
+    map: &'a mut OrderedMap<K, V, S>,
+    hash: u64,
+    key: usize,
+}
+
+impl<'a, K, V, S> OccupiedEntry<'a, K, V, S> {
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.map.list[self.key].0
+    }
+
+    #[inline]
+    pub fn get(&self) -> &V {
+        &self.map.list[self.key].1
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.list[self.key].1
+    }
+
+    #[inline]
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.list[self.key].1
+    }
+
+    #[inline]
+    pub fn insert(&mut self, v: V) -> V {
+        std::mem::replace(self.get_mut(), v)
+    }
+
+    #[inline]
+    pub fn move_to_front(&mut self) {
+        self.map.list.move_to_front(self.key);
+    }
+
+    #[inline]
+    pub fn move_to_back(&mut self) {
+        self.map.list.move_to_back(self.key);
+    }
+
+    #[inline]
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    #[inline]
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.index.remove(self.hash, self.key);
+        self.map.list.remove(self.key)
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for OccupiedEntry<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", self.key())
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+pub struct VacantEntry<'a, K, V, S> {
+    map: &'a mut OrderedMap<K, V, S>,
+    hash: u64,
+    k: K,
+}
+
+impl<'a, K, V, S> VacantEntry<'a, K, V, S> {
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.k
+    }
+
+    #[inline]
+    pub fn into_key(self) -> K {
+        self.k
+    }
+
+    /// Inserts the entry at the back of the map.
+    #[inline]
+    pub fn insert(self, v: V) -> &'a mut V {
+        let key = self.map.list.push_back((self.k, v));
+        self.map.index.insert(self.hash, key);
+        &mut self.map.list[key].1
+    }
+}
+
+impl<K: fmt::Debug, V, S> fmt::Debug for VacantEntry<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntry").field(self.key()).finish()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a OrderedMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = OrderedMapIter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut OrderedMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = OrderedMapIterMut<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, V, S> IntoIterator for OrderedMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = OrderedMapIntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        OrderedMapIntoIter {
+            inner: self.list.into_iter(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct OrderedMapIter<'a, K, V> {
+    inner: Iter<'a, (K, V)>,
+}
+
+impl<K, V> Clone for OrderedMapIter<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for OrderedMapIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| (k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for OrderedMapIter<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, v)| (k, v))
+    }
+}
+
+impl<K, V> ExactSizeIterator for OrderedMapIter<'_, K, V> {}
+
+impl<K, V> FusedIterator for OrderedMapIter<'_, K, V> {}
+
+#[derive(Debug)]
+pub struct OrderedMapIterMut<'a, K, V> {
+    inner: IterMut<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for OrderedMapIterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| (&*k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for OrderedMapIterMut<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, v)| (&*k, v))
+    }
+}
+
+impl<K, V> ExactSizeIterator for OrderedMapIterMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for OrderedMapIterMut<'_, K, V> {}
+
+#[derive(Debug)]
+pub struct OrderedMapKeys<'a, K, V> {
+    inner: OrderedMapIter<'a, K, V>,
+}
+
+impl<K, V> Clone for OrderedMapKeys<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for OrderedMapKeys<'a, K, V> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for OrderedMapKeys<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<K, V> ExactSizeIterator for OrderedMapKeys<'_, K, V> {}
+
+impl<K, V> FusedIterator for OrderedMapKeys<'_, K, V> {}
+
+#[derive(Debug)]
+pub struct OrderedMapValues<'a, K, V> {
+    inner: OrderedMapIter<'a, K, V>,
+}
+
+impl<K, V> Clone for OrderedMapValues<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for OrderedMapValues<'a, K, V> {
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for OrderedMapValues<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V> ExactSizeIterator for OrderedMapValues<'_, K, V> {}
+
+impl<K, V> FusedIterator for OrderedMapValues<'_, K, V> {}
+
+#[derive(Debug)]
+pub struct OrderedMapValuesMut<'a, K, V> {
+    inner: OrderedMapIterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for OrderedMapValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for OrderedMapValuesMut<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V> ExactSizeIterator for OrderedMapValuesMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for OrderedMapValuesMut<'_, K, V> {}
+
+#[derive(Debug)]
+pub struct OrderedMapIntoIter<K, V> {
+    inner: IntoIter<(K, V)>,
+}
+
+impl<K, V> Iterator for OrderedMapIntoIter<K, V> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for OrderedMapIntoIter<K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for OrderedMapIntoIter<K, V> {}
+
+impl<K, V> FusedIterator for OrderedMapIntoIter<K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys<K: Copy, V, S>(map: &OrderedMap<K, V, S>) -> Vec<K> {
+        map.keys().copied().collect()
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut map = OrderedMap::new();
+        assert_eq!(map.insert("b", 1), None);
+        assert_eq!(map.insert("a", 2), None);
+        assert_eq!(map.insert("c", 3), None);
+        assert_eq!(map.insert("a", 20), Some(2));
+        assert_eq!(keys(&map), ["b", "a", "c"]);
+        assert_eq!(map["a"], 20);
+        assert_eq!(map.get_key_value("c"), Some((&"c", &3)));
+
+        assert!(map.move_to_back("b"));
+        assert!(!map.move_to_back("z"));
+        assert_eq!(keys(&map), ["a", "c", "b"]);
+        assert!(map.move_to_front("c"));
+        assert_eq!(map.front(), Some((&"c", &3)));
+        assert_eq!(map.back(), Some((&"b", &1)));
+
+        assert_eq!(map.remove("a"), Some(20));
+        assert_eq!(map.remove("a"), None);
+        assert_eq!(map.pop_front(), Some(("c", 3)));
+        assert_eq!(map.pop_back(), Some(("b", 1)));
+        assert!(map.is_empty());
+        assert_eq!(map.pop_front(), None);
+    }
+
+    #[test]
+    fn entry() {
+        let mut map: OrderedMap<String, usize> = OrderedMap::new();
+        for word in "a b a c b a".split(' ') {
+            *map.entry(word.to_string()).or_default() += 1;
+        }
+        assert_eq!(
+            map.iter()
+                .map(|(k, v)| (k.as_str(), *v))
+                .collect::<Vec<_>>(),
+            [("a", 3), ("b", 2), ("c", 1)]
+        );
+
+        map.entry("b".to_string()).and_modify(|v| *v *= 10);
+        assert_eq!(map["b"], 20);
+        match map.entry("a".to_string()) {
+            Entry::Occupied(mut entry) => {
+                entry.move_to_back();
+                assert_eq!(entry.insert(30), 3);
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match map.entry("c".to_string()) {
+            Entry::Occupied(entry) => assert_eq!(entry.remove_entry(), ("c".to_string(), 1)),
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match map.entry("d".to_string()) {
+            Entry::Occupied(_) => unreachable!(),
+            Entry::Vacant(entry) => {
+                assert_eq!(entry.key(), "d");
+                *entry.insert(4) += 1;
+            }
+        }
+        assert_eq!(
+            map.into_iter().collect::<Vec<_>>(),
+            [
+                ("b".to_string(), 20),
+                ("a".to_string(), 30),
+                ("d".to_string(), 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn retain_and_iter() {
+        let mut map = (0..10).map(|i| (i, i * i)).collect::<OrderedMap<_, _>>();
+        map.retain(|k, v| {
+            *v += 1;
+            k % 3 == 0
+        });
+        assert_eq!(keys(&map), [0, 3, 6, 9]);
+        assert!(!map.contains_key(&1));
+        assert_eq!(map.get(&6), Some(&37));
+        for (v, k) in map.values_mut().rev().zip([9, 6, 3, 0]) {
+            *v = k;
+        }
+        assert_eq!(map.values().len(), 4);
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), [0, 3, 6, 9]);
+        assert_eq!(map.keys().next_back(), Some(&9));
+        for v in map.values_mut() {
+            *v = 0;
+        }
+        assert!(map.values().all(|v| *v == 0));
+        assert_eq!(map.iter().next_back(), Some((&9, &0)));
+
+        let other = OrderedMap::from([(0, 0), (3, 0), (6, 0), (9, 0)]);
+        assert_eq!(map, other);
+        map.move_to_back(&0);
+        assert_ne!(map, other);
+        assert_eq!(format!("{:?}", map), "{3: 0, 6: 0, 9: 0, 0: 0}");
+        map.clear();
+        assert_eq!(map.get(&3), None);
+    }
+}
@@ -0,0 +1,491 @@
+use crate::iter::Walk;
+use crate::Error;
+use slab::Slab;
+use std::iter::FusedIterator;
+use std::ops::{Index, IndexMut};
+
+/// Elements stored once in a slab and linked into up to `N` independent orderings ("lanes").
+///
+/// An element starts out unlinked in every lane after [`insert`](Self::insert); linking it
+/// into a lane never changes its key. Lanes outside `0..N` read as empty, and linking into one
+/// fails with [`Error::IndexOutOfBounds`].
+#[derive(Debug, Clone)]
+pub struct SlabMultiList<T, const N: usize> {
+    slab: Slab<Node<T, N>>,
+    lanes: [Ends; N],
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Ends {
+    head: Option<usize>,
+    tail: Option<usize>,
+    len: usize,
+}
+
+impl Ends {
+    const EMPTY: Self = Self {
+        head: None,
+        tail: None,
+        len: 0,
+    };
+}
+
+#[derive(Debug, Clone)]
+struct Node<T, const N: usize> {
+    value: T,
+    links: [Option<Link>; N],
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Link {
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+impl<T, const N: usize> Default for SlabMultiList<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            slab: Default::default(),
+            lanes: [Ends::EMPTY; N],
+        }
+    }
+}
+
+impl<T, const N: usize> SlabMultiList<T, N> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slab: Slab::with_capacity(capacity),
+            lanes: [Ends::EMPTY; N],
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slab.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.slab.is_empty()
+    }
+
+    #[inline]
+    pub fn lane_len(&self, lane: usize) -> usize {
+        self.lanes.get(lane).map_or(0, |ends| ends.len)
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.slab.contains(key)
+    }
+
+    #[inline]
+    pub fn is_linked(&self, lane: usize, key: usize) -> bool {
+        self.slab
+            .get(key)
+            .is_some_and(|node| matches!(node.links.get(lane), Some(Some(_))))
+    }
+
+    #[inline]
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.slab.get(key).map(|node| &node.value)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.slab.get_mut(key).map(|node| &mut node.value)
+    }
+
+    #[inline]
+    pub fn front_key(&self, lane: usize) -> Option<usize> {
+        self.lanes.get(lane)?.head
+    }
+
+    #[inline]
+    pub fn back_key(&self, lane: usize) -> Option<usize> {
+        self.lanes.get(lane)?.tail
+    }
+
+    #[inline]
+    pub fn next_key(&self, lane: usize, key: usize) -> Option<usize> {
+        (*self.slab.get(key)?.links.get(lane)?)?.next
+    }
+
+    #[inline]
+    pub fn prev_key(&self, lane: usize, key: usize) -> Option<usize> {
+        (*self.slab.get(key)?.links.get(lane)?)?.prev
+    }
+
+    #[inline]
+    pub fn iter(&self, lane: usize) -> LaneIter<'_, T, N> {
+        LaneIter {
+            slab: &self.slab,
+            walk: self.walk(lane),
+            lane,
+        }
+    }
+
+    #[inline]
+    pub fn keys(&self, lane: usize) -> LaneKeys<'_, T, N> {
+        LaneKeys {
+            slab: &self.slab,
+            walk: self.walk(lane),
+            lane,
+        }
+    }
+
+    /// Stores a value without linking it into any lane.
+    #[inline]
+    pub fn insert(&mut self, value: T) -> usize {
+        self.slab.insert(Node {
+            value,
+            links: [None; N],
+        })
+    }
+
+    /// Unlinks the element from every lane and removes it.
+    #[inline]
+    pub fn try_remove(&mut self, key: usize) -> Result<T, Error> {
+        if !self.slab.contains(key) {
+            return Err(Error::InvalidKey);
+        }
+        for lane in 0..N {
+            self.unlink_from(lane, key);
+        }
+        Ok(self.slab.remove(key).value)
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn remove(&mut self, key: usize) -> T {
+        self.try_remove(key).expect("invalid key")
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn push_front(&mut self, lane: usize, key: usize) {
+        if let Err(error) = self.try_push_front(lane, key) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn push_back(&mut self, lane: usize, key: usize) {
+        if let Err(error) = self.try_push_back(lane, key) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Links the element at the front of `lane`, moving it there if it is already linked.
+    #[inline]
+    pub fn try_push_front(&mut self, lane: usize, key: usize) -> Result<(), Error> {
+        if lane >= N {
+            return Err(Error::IndexOutOfBounds);
+        }
+        if !self.slab.contains(key) {
+            return Err(Error::InvalidKey);
+        }
+        self.unlink_from(lane, key);
+        let ends = &mut self.lanes[lane];
+        let next = ends.head.replace(key);
+        match next {
+            None => ends.tail = Some(key),
+            Some(next) => self.link_mut(lane, next).prev = Some(key),
+        }
+        self.lanes[lane].len += 1;
+        self.slab[key].links[lane] = Some(Link { prev: None, next });
+        Ok(())
+    }
+
+    /// Links the element at the back of `lane`, moving it there if it is already linked.
+    #[inline]
+    pub fn try_push_back(&mut self, lane: usize, key: usize) -> Result<(), Error> {
+        if lane >= N {
+            return Err(Error::IndexOutOfBounds);
+        }
+        if !self.slab.contains(key) {
+            return Err(Error::InvalidKey);
+        }
+        self.unlink_from(lane, key);
+        let ends = &mut self.lanes[lane];
+        let prev = ends.tail.replace(key);
+        match prev {
+            None => ends.head = Some(key),
+            Some(prev) => self.link_mut(lane, prev).next = Some(key),
+        }
+        self.lanes[lane].len += 1;
+        self.slab[key].links[lane] = Some(Link { prev, next: None });
+        Ok(())
+    }
+
+    /// Unlinks the element from `lane` only. Returns whether it was linked.
+    #[inline]
+    pub fn unlink(&mut self, lane: usize, key: usize) -> bool {
+        lane < N && self.slab.contains(key) && self.unlink_from(lane, key)
+    }
+
+    /// Unlinks the front element of `lane` and returns its key; the element stays stored.
+    #[inline]
+    pub fn pop_front(&mut self, lane: usize) -> Option<usize> {
+        let key = self.lanes.get(lane)?.head?;
+        self.unlink_from(lane, key);
+        Some(key)
+    }
+
+    /// Unlinks the back element of `lane` and returns its key; the element stays stored.
+    #[inline]
+    pub fn pop_back(&mut self, lane: usize) -> Option<usize> {
+        let key = self.lanes.get(lane)?.tail?;
+        self.unlink_from(lane, key);
+        Some(key)
+    }
+
+    #[inline]
+    fn ends(&self, lane: usize) -> Ends {
+        self.lanes.get(lane).copied().unwrap_or(Ends::EMPTY)
+    }
+
+    #[inline]
+    fn walk(&self, lane: usize) -> Walk {
+        let ends = self.ends(lane);
+        Walk::linked(ends.head, ends.tail, ends.len)
+    }
+
+    #[inline]
+    fn link_mut(&mut self, lane: usize, key: usize) -> &mut Link {
+        self.slab[key].links[lane].as_mut().unwrap()
+    }
+
+    #[inline]
+    fn unlink_from(&mut self, lane: usize, key: usize) -> bool {
+        let Some(Link { prev, next }) = self.slab[key].links[lane].take() else {
+            return false;
+        };
+        let ends = &mut self.lanes[lane];
+        ends.len -= 1;
+        match prev {
+            None => assert_eq!(std::mem::replace(&mut ends.head, next), Some(key)),
+            Some(prev) => assert_eq!(
+                std::mem::replace(&mut self.link_mut(lane, prev).next, next),
+                Some(key)
+            ),
+        }
+        let ends = &mut self.lanes[lane];
+        match next {
+            None => assert_eq!(std::mem::replace(&mut ends.tail, prev), Some(key)),
+            Some(next) => assert_eq!(
+                std::mem::replace(&mut self.link_mut(lane, next).prev, prev),
+                Some(key)
+            ),
+        }
+        true
+    }
+}
+
+impl<T, const N: usize> Index<usize> for SlabMultiList<T, N> {
+    type Output = T;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, key: usize) -> &Self::Output {
+        self.get(key).expect("invalid key")
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for SlabMultiList<T, N> {
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, key: usize) -> &mut Self::Output {
+        self.get_mut(key).expect("invalid key")
+    }
+}
+
+// Every key a lane walk reaches is linked into that lane.
+#[inline]
+fn lane_link<T, const N: usize>(slab: &Slab<Node<T, N>>, lane: usize, key: usize) -> Link {
+    unsafe { slab.get_unchecked(key) }.links[lane].expect("corrupt links")
+}
+
+#[derive(Debug)]
+pub struct LaneIter<'a, T, const N: usize> {
+    slab: &'a Slab<Node<T, N>>,
+    walk: Walk,
+    lane: usize,
+}
+
+impl<T, const N: usize> Clone for LaneIter<'_, T, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            slab: self.slab,
+            walk: self.walk,
+            lane: self.lane,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for LaneIter<'a, T, N> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (slab, lane) = (self.slab, self.lane);
+        let key = self.walk.next_by(|key| lane_link(slab, lane, key).next)?;
+        Some(unsafe { &self.slab.get_unchecked(key).value })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.walk.len(), Some(self.walk.len()))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for LaneIter<'_, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (slab, lane) = (self.slab, self.lane);
+        let key = self
+            .walk
+            .next_back_by(|key| lane_link(slab, lane, key).prev)?;
+        Some(unsafe { &self.slab.get_unchecked(key).value })
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for LaneIter<'_, T, N> {}
+
+impl<T, const N: usize> FusedIterator for LaneIter<'_, T, N> {}
+
+#[derive(Debug)]
+pub struct LaneKeys<'a, T, const N: usize> {
+    slab: &'a Slab<Node<T, N>>,
+    walk: Walk,
+    lane: usize,
+}
+
+impl<T, const N: usize> Clone for LaneKeys<'_, T, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            slab: self.slab,
+            walk: self.walk,
+            lane: self.lane,
+        }
+    }
+}
+
+impl<T, const N: usize> Iterator for LaneKeys<'_, T, N> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (slab, lane) = (self.slab, self.lane);
+        self.walk.next_by(|key| lane_link(slab, lane, key).next)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.walk.len(), Some(self.walk.len()))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for LaneKeys<'_, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (slab, lane) = (self.slab, self.lane);
+        self.walk
+            .next_back_by(|key| lane_link(slab, lane, key).prev)
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for LaneKeys<'_, T, N> {}
+
+impl<T, const N: usize> FusedIterator for LaneKeys<'_, T, N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LRU: usize = 0;
+    const EXPIRY: usize = 1;
+
+    #[test]
+    fn lanes() {
+        let mut list = SlabMultiList::<&str, 2>::new();
+        let a = list.insert("a");
+        let b = list.insert("b");
+        let c = list.insert("c");
+        assert!(!list.is_linked(LRU, a));
+
+        for key in [a, b, c] {
+            list.push_back(LRU, key);
+            list.push_front(EXPIRY, key);
+        }
+        assert_eq!(list.iter(LRU).copied().collect::<Vec<_>>(), ["a", "b", "c"]);
+        assert_eq!(
+            list.iter(EXPIRY).copied().collect::<Vec<_>>(),
+            ["c", "b", "a"]
+        );
+        assert_eq!(list.keys(LRU).rev().collect::<Vec<_>>(), [c, b, a]);
+
+        list.push_back(LRU, a);
+        assert_eq!(list.keys(LRU).collect::<Vec<_>>(), [b, c, a]);
+        assert_eq!(list.keys(EXPIRY).collect::<Vec<_>>(), [c, b, a]);
+        assert_eq!(list.lane_len(LRU), 3);
+
+        assert!(list.unlink(EXPIRY, b));
+        assert!(!list.unlink(EXPIRY, b));
+        assert!(list.is_linked(LRU, b));
+        assert_eq!(list.keys(EXPIRY).collect::<Vec<_>>(), [c, a]);
+        assert_eq!(list.next_key(EXPIRY, c), Some(a));
+        assert_eq!(list.prev_key(LRU, c), Some(b));
+        assert_eq!(list.next_key(EXPIRY, b), None);
+
+        assert_eq!(list.pop_front(EXPIRY), Some(c));
+        assert!(list.contains_key(c));
+        assert_eq!(list.remove(c), "c");
+        assert_eq!(list.keys(LRU).collect::<Vec<_>>(), [b, a]);
+        assert_eq!(list.keys(EXPIRY).collect::<Vec<_>>(), [a]);
+        assert_eq!(list.front_key(EXPIRY), Some(a));
+        assert_eq!(list.back_key(LRU), Some(a));
+
+        list[a] = "x";
+        assert_eq!(list.pop_back(LRU), Some(a));
+        assert_eq!(list.iter(LRU).copied().collect::<Vec<_>>(), ["b"]);
+        assert_eq!(list.iter(EXPIRY).copied().collect::<Vec<_>>(), ["x"]);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.try_push_back(LRU, c), Err(Error::InvalidKey));
+    }
+
+    #[test]
+    fn out_of_range_lane() {
+        let mut list = SlabMultiList::<_, 2>::new();
+        let a = list.insert("a");
+        list.push_back(0, a);
+        assert_eq!(list.try_push_back(2, a), Err(Error::IndexOutOfBounds));
+        assert_eq!(list.try_push_front(2, 100), Err(Error::IndexOutOfBounds));
+        assert_eq!(list.lane_len(2), 0);
+        assert!(!list.is_linked(2, a));
+        assert!(!list.unlink(2, a));
+        assert_eq!(list.front_key(2), None);
+        assert_eq!(list.next_key(2, a), None);
+        assert_eq!(list.pop_back(2), None);
+        assert_eq!(list.iter(2).next(), None);
+        assert_eq!(list.keys(2).len(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn push_to_out_of_range_lane() {
+        let mut list = SlabMultiList::<_, 1>::new();
+        let a = list.insert(0);
+        list.push_front(1, a);
+    }
+}
@@ -0,0 +1,479 @@
+use crate::{Error, InsertError, Iter, IterMut, Keys, SlabLinkedList};
+use std::cmp::Ordering;
+use std::ops::{Index, IndexMut};
+
+// Elements are kept in groups of consecutive elements with labels local to the group, and the
+// groups themselves are labelled. A group is split once it grows past `GROUP_MAX`.
+const GROUP_MAX: usize = 64;
+// Density threshold for relabelling groups: a label range of size `2^j` may hold fewer than
+// `DENSITY_THRESHOLD^j` groups.
+const DENSITY_THRESHOLD: f64 = 1.5;
+
+/// A [`SlabLinkedList`] that maintains order labels, so that the relative order of any two
+/// elements can be compared in O(1).
+///
+/// Labels follow the two-level scheme of Dietz and Sleator. Each element has a label within its
+/// group of at most 64 consecutive elements, which is reassigned across the whole group in
+/// O(64) when two neighbours run out of room. Only group splits, at most one per 32 insertions,
+/// touch the group labels, which are relabelled over the smallest sufficiently sparse range
+/// (Bender et al.) in amortised O(log n). Insertion is therefore amortised O(1) for any list
+/// that fits in memory. Removal never relabels, and a full relabel of the groups is the
+/// fallback if the 64-bit label space becomes too dense.
+#[derive(Debug, Clone)]
+pub struct LabeledList<T> {
+    list: SlabLinkedList<T>,
+    tags: Vec<Tag>,
+    groups: SlabLinkedList<Group>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Tag {
+    group: usize,
+    label: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Group {
+    first: usize,
+    len: usize,
+    label: u64,
+}
+
+impl<T> Default for LabeledList<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            list: Default::default(),
+            tags: Default::default(),
+            groups: Default::default(),
+        }
+    }
+}
+
+impl<T> LabeledList<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            list: SlabLinkedList::with_capacity(capacity),
+            tags: Vec::with_capacity(capacity),
+            groups: SlabLinkedList::new(),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    #[inline]
+    pub fn as_list(&self) -> &SlabLinkedList<T> {
+        &self.list
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.list.contains_key(key)
+    }
+
+    #[inline]
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.list.get(key)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.list.get_mut(key)
+    }
+
+    #[inline]
+    pub fn front_key(&self) -> Option<usize> {
+        self.list.front_key()
+    }
+
+    #[inline]
+    pub fn back_key(&self) -> Option<usize> {
+        self.list.back_key()
+    }
+
+    #[inline]
+    pub fn next_key(&self, key: usize) -> Option<usize> {
+        self.list.next_key(key)
+    }
+
+    #[inline]
+    pub fn prev_key(&self, key: usize) -> Option<usize> {
+        self.list.prev_key(key)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.list.iter()
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.list.iter_mut()
+    }
+
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, T> {
+        self.list.keys()
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn cmp_order(&self, a: usize, b: usize) -> Ordering {
+        self.try_cmp_order(a, b).expect("invalid key")
+    }
+
+    /// Compares the positions of two elements in O(1).
+    #[inline]
+    pub fn try_cmp_order(&self, a: usize, b: usize) -> Result<Ordering, Error> {
+        if !self.list.contains_key(a) || !self.list.contains_key(b) {
+            return Err(Error::InvalidKey);
+        }
+        Ok(self.order_label(a).cmp(&self.order_label(b)))
+    }
+
+    #[inline]
+    fn order_label(&self, key: usize) -> (u64, u64) {
+        let tag = self.tags[key];
+        (self.groups[tag.group].label, tag.label)
+    }
+
+    #[inline]
+    pub fn push_front(&mut self, value: T) -> usize {
+        match self.list.front_key() {
+            None => self.insert_first(value),
+            Some(target_key) => self.insert_before(value, target_key),
+        }
+    }
+
+    #[inline]
+    pub fn push_back(&mut self, value: T) -> usize {
+        match self.list.back_key() {
+            None => self.insert_first(value),
+            Some(target_key) => self.insert_after(value, target_key),
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn insert_before(&mut self, value: T, target_key: usize) -> usize {
+        match self.try_insert_before(value, target_key) {
+            Ok(key) => key,
+            Err(_) => panic!("invalid key"),
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn insert_after(&mut self, value: T, target_key: usize) -> usize {
+        match self.try_insert_after(value, target_key) {
+            Ok(key) => key,
+            Err(_) => panic!("invalid key"),
+        }
+    }
+
+    pub fn try_insert_before(
+        &mut self,
+        value: T,
+        target_key: usize,
+    ) -> Result<usize, InsertError<T>> {
+        let key = self.list.try_insert_before(value, target_key)?;
+        let group = self.tags[target_key].group;
+        let hi = self.tags[target_key].label;
+        let lo = match self.list.prev_key(key) {
+            Some(prev) if self.tags[prev].group == group => self.tags[prev].label,
+            _ => {
+                self.groups[group].first = key;
+                0
+            }
+        };
+        self.place(key, group, lo, hi);
+        Ok(key)
+    }
+
+    pub fn try_insert_after(
+        &mut self,
+        value: T,
+        target_key: usize,
+    ) -> Result<usize, InsertError<T>> {
+        let key = self.list.try_insert_after(value, target_key)?;
+        let group = self.tags[target_key].group;
+        let lo = self.tags[target_key].label;
+        let hi = match self.list.next_key(key) {
+            Some(next) if self.tags[next].group == group => self.tags[next].label,
+            _ => u64::MAX,
+        };
+        self.place(key, group, lo, hi);
+        Ok(key)
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn remove(&mut self, key: usize) -> T {
+        self.try_remove(key).expect("invalid key")
+    }
+
+    pub fn try_remove(&mut self, key: usize) -> Result<T, Error> {
+        let next = self.list.next_key(key);
+        let value = self.list.try_remove(key)?;
+        let group = self.tags[key].group;
+        let len = &mut self.groups[group].len;
+        *len -= 1;
+        if *len == 0 {
+            self.groups.remove(group);
+        } else if self.groups[group].first == key {
+            self.groups[group].first = next.unwrap();
+        }
+        Ok(value)
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.tags.clear();
+        self.groups.clear();
+    }
+
+    fn insert_first(&mut self, value: T) -> usize {
+        let key = self.list.push_back(value);
+        let group = self.groups.push_back(Group {
+            first: key,
+            len: 1,
+            label: u64::MAX / 2,
+        });
+        self.set_tag(
+            key,
+            Tag {
+                group,
+                label: u64::MAX / 2,
+            },
+        );
+        key
+    }
+
+    #[inline]
+    fn set_tag(&mut self, key: usize, tag: Tag) {
+        if self.tags.len() <= key {
+            self.tags.resize(key + 1, Tag::default());
+        }
+        self.tags[key] = tag;
+    }
+
+    // Labels a new element of `group` between the exclusive bounds `lo` and `hi`.
+    fn place(&mut self, key: usize, group: usize, lo: u64, hi: u64) {
+        self.set_tag(
+            key,
+            Tag {
+                group,
+                label: lo + (hi - lo) / 2,
+            },
+        );
+        self.groups[group].len += 1;
+        if hi - lo < 2 {
+            self.relabel_group(group);
+        }
+        if self.groups[group].len > GROUP_MAX {
+            self.split_group(group);
+        }
+    }
+
+    fn relabel_group(&mut self, group: usize) {
+        let Group { first, len, .. } = self.groups[group];
+        let step = u64::MAX / (len as u64 + 1);
+        let mut key = first;
+        for i in 1..=len as u64 {
+            self.tags[key].label = step * i;
+            if i < len as u64 {
+                key = self.list.next_key(key).unwrap();
+            }
+        }
+    }
+
+    fn split_group(&mut self, group: usize) {
+        let len = self.groups[group].len;
+        let mut first = self.groups[group].first;
+        for _ in 0..len / 2 {
+            first = self.list.next_key(first).unwrap();
+        }
+        self.groups[group].len = len / 2;
+        let new_group = self.groups.insert_after(
+            Group {
+                first,
+                len: len - len / 2,
+                label: 0,
+            },
+            group,
+        );
+        let mut key = first;
+        for _ in 0..len - len / 2 {
+            self.tags[key].group = new_group;
+            key = self.list.next_key(key).unwrap_or(key);
+        }
+        self.label_group(new_group);
+        self.relabel_group(group);
+        self.relabel_group(new_group);
+    }
+
+    // Gives a freshly inserted group a label after its predecessor, relabelling the smallest
+    // enclosing power-of-two label range that is sparse enough.
+    fn label_group(&mut self, group: usize) {
+        let prev = self.groups.prev_key(group).unwrap();
+        let lo = self.groups[prev].label;
+        let hi = self
+            .groups
+            .next_key(group)
+            .map_or(u64::MAX, |next| self.groups[next].label);
+        if hi - lo >= 2 {
+            self.groups[group].label = lo + (hi - lo) / 2;
+            return;
+        }
+        let (mut left, mut right, mut count) = (prev, group, 2_u64);
+        let mut threshold = 1.0;
+        let mut bits = 0;
+        let (start, end) = loop {
+            bits += 1;
+            threshold *= DENSITY_THRESHOLD;
+            let mask = u64::MAX >> (64 - bits);
+            let (start, end) = (lo & !mask, lo | mask);
+            while let Some(key) = self.groups.prev_key(left) {
+                if self.groups[key].label < start {
+                    break;
+                }
+                left = key;
+                count += 1;
+            }
+            while let Some(key) = self.groups.next_key(right) {
+                if self.groups[key].label > end {
+                    break;
+                }
+                right = key;
+                count += 1;
+            }
+            if (count as f64) < threshold || bits == 64 {
+                break (start, end);
+            }
+        };
+        let step = (end - start) / (count + 1);
+        let mut key = left;
+        for i in 1..=count {
+            self.groups[key].label = start + step * i;
+            if let Some(next) = self.groups.next_key(key) {
+                key = next;
+            }
+        }
+    }
+}
+
+impl<T> Index<usize> for LabeledList<T> {
+    type Output = T;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, key: usize) -> &T {
+        &self.list[key]
+    }
+}
+
+impl<T> IndexMut<usize> for LabeledList<T> {
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, key: usize) -> &mut T {
+        &mut self.list[key]
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LabeledList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::Rng;
+
+    fn check<T>(list: &LabeledList<T>) {
+        let keys = list.keys().collect::<Vec<_>>();
+        for pair in keys.windows(2) {
+            assert_eq!(list.cmp_order(pair[0], pair[1]), Ordering::Less);
+            assert_eq!(list.cmp_order(pair[1], pair[0]), Ordering::Greater);
+        }
+        let len = list.groups.iter().map(|group| group.len).sum::<usize>();
+        assert_eq!(len, list.len());
+        assert!(list.groups.iter().all(|group| group.len <= GROUP_MAX));
+    }
+
+    #[test]
+    fn cmp_order() {
+        let mut list = LabeledList::new();
+        let b = list.push_back('b');
+        let a = list.push_front('a');
+        let d = list.push_back('d');
+        let c = list.insert_before('c', d);
+        assert_eq!(list.iter().collect::<String>(), "abcd");
+        assert_eq!(list.cmp_order(a, d), Ordering::Less);
+        assert_eq!(list.cmp_order(c, b), Ordering::Greater);
+        assert_eq!(list.cmp_order(c, c), Ordering::Equal);
+        assert_eq!(list.remove(b), 'b');
+        assert_eq!(list.try_cmp_order(a, b), Err(Error::InvalidKey));
+        assert!(list.try_insert_after('x', b).is_err());
+        check(&list);
+    }
+
+    #[test]
+    fn dense_insertions() {
+        // Always inserting right after the same element exhausts the local labels quickly and
+        // forces splits with clustered group labels.
+        let mut list = LabeledList::new();
+        let first = list.push_back(0);
+        let last = list.push_back(0);
+        for i in 1..5000 {
+            list.insert_after(i, first);
+        }
+        check(&list);
+        let mut key = last;
+        for i in 0..5000 {
+            key = list.insert_before(i, key);
+        }
+        check(&list);
+        assert_eq!(list.len(), 10001);
+    }
+
+    #[test]
+    fn random_edits() {
+        let mut rng = Rng::new(0x9e37_79b9_7f4a_7c15);
+        let mut rand = || rng.next_u64() as usize;
+        let mut list = LabeledList::new();
+        let mut keys = vec![list.push_back(0)];
+        for i in 0..4000 {
+            let target = keys[rand() % keys.len()];
+            match rand() % 5 {
+                0 if keys.len() > 1 => {
+                    let index = keys.iter().position(|&key| key == target).unwrap();
+                    list.remove(keys.swap_remove(index));
+                }
+                1 | 2 => keys.push(list.insert_before(i, target)),
+                _ => keys.push(list.insert_after(i, target)),
+            }
+        }
+        check(&list);
+    }
+}
@@ -0,0 +1,786 @@
+use crate::generational::Generations;
+use crate::iter::{Links, ValueCell, Walk};
+use crate::{Error, InsertError};
+use slab::Slab;
+use std::iter::FusedIterator;
+use std::ops::{Index, IndexMut};
+
+/// Identifies a list in a [`ListPool`]. Ids of removed lists are never accepted again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ListId {
+    index: usize,
+    generation: u32,
+}
+
+/// Many linked lists sharing one slab, so that an element keeps its key when it is moved
+/// from one list to another.
+#[derive(Debug, Clone)]
+pub struct ListPool<T> {
+    slab: Slab<Node<T>>,
+    lists: Slab<Ends>,
+    generations: Generations,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Ends {
+    head: Option<usize>,
+    tail: Option<usize>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    value: ValueCell<T>,
+    list: usize,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+impl<T> Default for ListPool<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            slab: Default::default(),
+            lists: Default::default(),
+            generations: Default::default(),
+        }
+    }
+}
+
+impl<T> ListPool<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slab: Slab::with_capacity(capacity),
+            lists: Default::default(),
+            generations: Default::default(),
+        }
+    }
+
+    /// Number of elements across all lists.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slab.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.slab.is_empty()
+    }
+
+    #[inline]
+    fn check(&self, list: ListId) -> Option<usize> {
+        let generation = self.generations.get(list.index);
+        (generation == list.generation && self.lists.contains(list.index)).then_some(list.index)
+    }
+
+    #[inline]
+    fn ends(&self, list: ListId) -> Option<&Ends> {
+        self.lists.get(self.check(list)?)
+    }
+
+    #[inline]
+    fn id(&self, index: usize) -> ListId {
+        ListId {
+            index,
+            generation: self.generations.get(index),
+        }
+    }
+
+    #[inline]
+    pub fn create_list(&mut self) -> ListId {
+        let index = self.lists.insert(Ends::default());
+        ListId {
+            index,
+            generation: self.generations.issue(index),
+        }
+    }
+
+    /// Removes a list, returning its elements in order. Its id is rejected from then on.
+    pub fn remove_list(&mut self, list: ListId) -> Option<Vec<T>> {
+        let index = self.check(list)?;
+        let mut values = Vec::with_capacity(self.lists[index].len);
+        while let Some(value) = self.pop_front(list) {
+            values.push(value);
+        }
+        self.lists.remove(index);
+        self.generations.retire(index);
+        Some(values)
+    }
+
+    #[inline]
+    pub fn contains_list(&self, list: ListId) -> bool {
+        self.check(list).is_some()
+    }
+
+    #[inline]
+    pub fn list_len(&self, list: ListId) -> usize {
+        self.ends(list).map_or(0, |ends| ends.len)
+    }
+
+    #[inline]
+    pub fn list_is_empty(&self, list: ListId) -> bool {
+        self.list_len(list) == 0
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.slab.contains(key)
+    }
+
+    #[inline]
+    pub fn list_of(&self, key: usize) -> Option<ListId> {
+        self.slab.get(key).map(|node| self.id(node.list))
+    }
+
+    #[inline]
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.slab.get(key).map(|node| node.value.get())
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.slab.get_mut(key).map(|node| node.value.get_mut())
+    }
+
+    #[inline]
+    pub fn front_key(&self, list: ListId) -> Option<usize> {
+        self.ends(list)?.head
+    }
+
+    #[inline]
+    pub fn back_key(&self, list: ListId) -> Option<usize> {
+        self.ends(list)?.tail
+    }
+
+    #[inline]
+    pub fn next_key(&self, key: usize) -> Option<usize> {
+        self.slab.get(key)?.next
+    }
+
+    #[inline]
+    pub fn prev_key(&self, key: usize) -> Option<usize> {
+        self.slab.get(key)?.prev
+    }
+
+    #[inline]
+    pub fn front(&self, list: ListId) -> Option<&T> {
+        self.get(self.front_key(list)?)
+    }
+
+    #[inline]
+    pub fn back(&self, list: ListId) -> Option<&T> {
+        self.get(self.back_key(list)?)
+    }
+
+    #[inline]
+    fn walk(&self, list: ListId) -> Walk {
+        match self.ends(list) {
+            Some(ends) => Walk::linked(ends.head, ends.tail, ends.len),
+            None => Walk::linked(None, None, 0),
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self, list: ListId) -> ListPoolIter<'_, T> {
+        ListPoolIter {
+            slab: &self.slab,
+            walk: self.walk(list),
+        }
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self, list: ListId) -> ListPoolIterMut<'_, T> {
+        ListPoolIterMut {
+            slab: &self.slab,
+            walk: self.walk(list),
+        }
+    }
+
+    #[inline]
+    pub fn keys(&self, list: ListId) -> ListPoolKeys<'_, T> {
+        ListPoolKeys {
+            slab: &self.slab,
+            walk: self.walk(list),
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn push_front(&mut self, list: ListId, value: T) -> usize {
+        match self.try_push_front(list, value) {
+            Ok(key) => key,
+            Err(_) => panic!("invalid list"),
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn push_back(&mut self, list: ListId, value: T) -> usize {
+        match self.try_push_back(list, value) {
+            Ok(key) => key,
+            Err(_) => panic!("invalid list"),
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn insert_before(&mut self, value: T, target_key: usize) -> usize {
+        match self.try_insert_before(value, target_key) {
+            Ok(key) => key,
+            Err(_) => panic!("invalid key"),
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn insert_after(&mut self, value: T, target_key: usize) -> usize {
+        match self.try_insert_after(value, target_key) {
+            Ok(key) => key,
+            Err(_) => panic!("invalid key"),
+        }
+    }
+
+    #[inline]
+    pub fn try_push_front(&mut self, list: ListId, value: T) -> Result<usize, InsertError<T>> {
+        let Some(index) = self.check(list) else {
+            return Err(InsertError::new(Error::InvalidList, value));
+        };
+        let key = self.insert_node(value);
+        self.link_front(key, index);
+        Ok(key)
+    }
+
+    #[inline]
+    pub fn try_push_back(&mut self, list: ListId, value: T) -> Result<usize, InsertError<T>> {
+        let Some(index) = self.check(list) else {
+            return Err(InsertError::new(Error::InvalidList, value));
+        };
+        let key = self.insert_node(value);
+        self.link_back(key, index);
+        Ok(key)
+    }
+
+    #[inline]
+    pub fn try_insert_before(
+        &mut self,
+        value: T,
+        target_key: usize,
+    ) -> Result<usize, InsertError<T>> {
+        if !self.slab.contains(target_key) {
+            return Err(InsertError::new(Error::InvalidKey, value));
+        }
+        let key = self.insert_node(value);
+        self.link_before(key, target_key);
+        Ok(key)
+    }
+
+    #[inline]
+    pub fn try_insert_after(
+        &mut self,
+        value: T,
+        target_key: usize,
+    ) -> Result<usize, InsertError<T>> {
+        if !self.slab.contains(target_key) {
+            return Err(InsertError::new(Error::InvalidKey, value));
+        }
+        let key = self.insert_node(value);
+        self.link_after(key, target_key);
+        Ok(key)
+    }
+
+    #[inline]
+    pub fn pop_front(&mut self, list: ListId) -> Option<T> {
+        let key = self.front_key(list)?;
+        Some(self.remove(key))
+    }
+
+    #[inline]
+    pub fn pop_back(&mut self, list: ListId) -> Option<T> {
+        let key = self.back_key(list)?;
+        Some(self.remove(key))
+    }
+
+    #[inline]
+    pub fn try_remove(&mut self, key: usize) -> Result<T, Error> {
+        if !self.slab.contains(key) {
+            return Err(Error::InvalidKey);
+        }
+        self.unlink(key);
+        Ok(self.slab.remove(key).value.into_inner())
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn remove(&mut self, key: usize) -> T {
+        self.try_remove(key).expect("invalid key")
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn move_to_front(&mut self, key: usize, list: ListId) {
+        if let Err(error) = self.try_move_to_front(key, list) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn move_to_back(&mut self, key: usize, list: ListId) {
+        if let Err(error) = self.try_move_to_back(key, list) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn move_before(&mut self, key: usize, target_key: usize) {
+        if let Err(error) = self.try_move_before(key, target_key) {
+            panic!("{}", error);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn move_after(&mut self, key: usize, target_key: usize) {
+        if let Err(error) = self.try_move_after(key, target_key) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Moves an element to the front of `list`, which may be the list it is already in.
+    #[inline]
+    pub fn try_move_to_front(&mut self, key: usize, list: ListId) -> Result<(), Error> {
+        if !self.slab.contains(key) {
+            return Err(Error::InvalidKey);
+        }
+        let Some(index) = self.check(list) else {
+            return Err(Error::InvalidList);
+        };
+        self.unlink(key);
+        self.link_front(key, index);
+        Ok(())
+    }
+
+    /// Moves an element to the back of `list`, which may be the list it is already in.
+    #[inline]
+    pub fn try_move_to_back(&mut self, key: usize, list: ListId) -> Result<(), Error> {
+        if !self.slab.contains(key) {
+            return Err(Error::InvalidKey);
+        }
+        let Some(index) = self.check(list) else {
+            return Err(Error::InvalidList);
+        };
+        self.unlink(key);
+        self.link_back(key, index);
+        Ok(())
+    }
+
+    /// Moves an element right before `target_key`, into whichever list that is in.
+    #[inline]
+    pub fn try_move_before(&mut self, key: usize, target_key: usize) -> Result<(), Error> {
+        if key == target_key {
+            return Err(Error::SameKey);
+        }
+        if !self.slab.contains(key) || !self.slab.contains(target_key) {
+            return Err(Error::InvalidKey);
+        }
+        self.unlink(key);
+        self.link_before(key, target_key);
+        Ok(())
+    }
+
+    /// Moves an element right after `target_key`, into whichever list that is in.
+    #[inline]
+    pub fn try_move_after(&mut self, key: usize, target_key: usize) -> Result<(), Error> {
+        if key == target_key {
+            return Err(Error::SameKey);
+        }
+        if !self.slab.contains(key) || !self.slab.contains(target_key) {
+            return Err(Error::InvalidKey);
+        }
+        self.unlink(key);
+        self.link_after(key, target_key);
+        Ok(())
+    }
+
+    #[inline]
+    fn insert_node(&mut self, value: T) -> usize {
+        self.slab.insert(Node {
+            value: ValueCell::new(value),
+            list: usize::MAX,
+            prev: None,
+            next: None,
+        })
+    }
+
+    #[inline]
+    fn link_front(&mut self, key: usize, list: usize) {
+        match self.lists[list].head {
+            None => self.link_first(key, list),
+            Some(head) => self.link_before(key, head),
+        }
+    }
+
+    #[inline]
+    fn link_back(&mut self, key: usize, list: usize) {
+        match self.lists[list].tail {
+            None => self.link_first(key, list),
+            Some(tail) => self.link_after(key, tail),
+        }
+    }
+
+    #[inline]
+    fn link_first(&mut self, key: usize, list: usize) {
+        let ends = &mut self.lists[list];
+        debug_assert_eq!(ends.len, 0);
+        ends.head.replace(key);
+        ends.tail.replace(key);
+        ends.len = 1;
+        self.slab[key].list = list;
+    }
+
+    #[inline]
+    fn link_before(&mut self, key: usize, target_key: usize) {
+        let (node, target) = self.slab.get2_mut(key, target_key).unwrap();
+        let list = target.list;
+        node.list = list;
+        node.next.replace(target_key);
+        match target.prev.replace(key) {
+            None => {
+                // target is head
+                assert_eq!(self.lists[list].head.replace(key), Some(target_key));
+            }
+            Some(prev) => {
+                node.prev.replace(prev);
+                assert_eq!(self.slab[prev].next.replace(key), Some(target_key));
+            }
+        }
+        self.lists[list].len += 1;
+    }
+
+    #[inline]
+    fn link_after(&mut self, key: usize, target_key: usize) {
+        let (node, target) = self.slab.get2_mut(key, target_key).unwrap();
+        let list = target.list;
+        node.list = list;
+        node.prev.replace(target_key);
+        match target.next.replace(key) {
+            None => {
+                // target is tail
+                assert_eq!(self.lists[list].tail.replace(key), Some(target_key));
+            }
+            Some(next) => {
+                node.next.replace(next);
+                assert_eq!(self.slab[next].prev.replace(key), Some(target_key));
+            }
+        }
+        self.lists[list].len += 1;
+    }
+
+    #[inline]
+    fn unlink(&mut self, key: usize) {
+        let node = &mut self.slab[key];
+        let list = node.list;
+        let prev = node.prev.take();
+        let next = node.next.take();
+        let ends = &mut self.lists[list];
+        ends.len -= 1;
+        match prev {
+            None => assert_eq!(std::mem::replace(&mut ends.head, next), Some(key)),
+            Some(prev) => assert_eq!(
+                std::mem::replace(&mut self.slab[prev].next, next),
+                Some(key)
+            ),
+        }
+        let ends = &mut self.lists[list];
+        match next {
+            None => assert_eq!(std::mem::replace(&mut ends.tail, prev), Some(key)),
+            Some(next) => assert_eq!(
+                std::mem::replace(&mut self.slab[next].prev, prev),
+                Some(key)
+            ),
+        }
+    }
+}
+
+impl<T> Index<usize> for ListPool<T> {
+    type Output = T;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, key: usize) -> &Self::Output {
+        self.get(key).expect("invalid key")
+    }
+}
+
+impl<T> IndexMut<usize> for ListPool<T> {
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, key: usize) -> &mut Self::Output {
+        self.get_mut(key).expect("invalid key")
+    }
+}
+
+impl<T> Links for Node<T> {
+    #[inline]
+    fn next(&self) -> Option<usize> {
+        self.next
+    }
+
+    #[inline]
+    fn prev(&self) -> Option<usize> {
+        self.prev
+    }
+}
+
+#[derive(Debug)]
+pub struct ListPoolIter<'a, T> {
+    slab: &'a Slab<Node<T>>,
+    walk: Walk,
+}
+
+impl<T> Clone for ListPoolIter<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            slab: self.slab,
+            walk: self.walk,
+        }
+    }
+}
+
+impl<'a, T> Iterator for ListPoolIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.walk.next(self.slab)?;
+        Some(unsafe { self.slab.get_unchecked(key) }.value.get())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.walk.len(), Some(self.walk.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for ListPoolIter<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.walk.next_back(self.slab)?;
+        Some(unsafe { self.slab.get_unchecked(key) }.value.get())
+    }
+}
+
+impl<T> ExactSizeIterator for ListPoolIter<'_, T> {}
+
+impl<T> FusedIterator for ListPoolIter<'_, T> {}
+
+#[derive(Debug)]
+pub struct ListPoolIterMut<'a, T> {
+    slab: &'a Slab<Node<T>>,
+    walk: Walk,
+}
+
+// Holds the unique borrow of the pool, like `&'a mut T` would.
+unsafe impl<T: Send> Send for ListPoolIterMut<'_, T> {}
+
+impl<'a, T> Iterator for ListPoolIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.walk.next(self.slab)?;
+        Some(unsafe { &mut *self.slab.get_unchecked(key).value.as_ptr() })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.walk.len(), Some(self.walk.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for ListPoolIterMut<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.walk.next_back(self.slab)?;
+        Some(unsafe { &mut *self.slab.get_unchecked(key).value.as_ptr() })
+    }
+}
+
+impl<T> ExactSizeIterator for ListPoolIterMut<'_, T> {}
+
+impl<T> FusedIterator for ListPoolIterMut<'_, T> {}
+
+#[derive(Debug)]
+pub struct ListPoolKeys<'a, T> {
+    slab: &'a Slab<Node<T>>,
+    walk: Walk,
+}
+
+impl<T> Clone for ListPoolKeys<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            slab: self.slab,
+            walk: self.walk,
+        }
+    }
+}
+
+impl<T> Iterator for ListPoolKeys<'_, T> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.walk.next(self.slab)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.walk.len(), Some(self.walk.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for ListPoolKeys<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.walk.next_back(self.slab)
+    }
+}
+
+impl<T> ExactSizeIterator for ListPoolKeys<'_, T> {}
+
+impl<T> FusedIterator for ListPoolKeys<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values<T: Copy>(pool: &ListPool<T>, list: ListId) -> Vec<T> {
+        pool.iter(list).copied().collect()
+    }
+
+    #[test]
+    fn push_and_pop() {
+        let mut pool = ListPool::new();
+        let a = pool.create_list();
+        let b = pool.create_list();
+        let a1 = pool.push_back(a, 1);
+        pool.push_back(a, 2);
+        pool.push_front(a, 0);
+        let b1 = pool.push_back(b, 10);
+        pool.insert_before(9, b1);
+        pool.insert_after(11, b1);
+        pool.insert_after(3, pool.back_key(a).unwrap());
+
+        assert_eq!(values(&pool, a), [0, 1, 2, 3]);
+        assert_eq!(values(&pool, b), [9, 10, 11]);
+        assert_eq!(pool.iter(b).rev().copied().collect::<Vec<_>>(), [11, 10, 9]);
+        assert_eq!(pool.len(), 7);
+        assert_eq!(pool.list_len(a), 4);
+        assert_eq!(pool.list_of(a1), Some(a));
+        assert_eq!(pool.list_of(b1), Some(b));
+        assert_eq!(pool.next_key(a1), pool.keys(a).nth(2));
+
+        assert_eq!(pool.pop_front(a), Some(0));
+        assert_eq!(pool.pop_back(b), Some(11));
+        assert_eq!(pool.remove(a1), 1);
+        assert_eq!(values(&pool, a), [2, 3]);
+        assert_eq!(pool.front(b), Some(&9));
+        assert_eq!(pool.back(b), Some(&10));
+
+        for value in pool.iter_mut(b) {
+            *value *= 2;
+        }
+        assert_eq!(pool[b1], 20);
+        assert_eq!(pool.remove_list(a), Some(vec![2, 3]));
+        assert!(!pool.contains_list(a));
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.iter(a).next(), None);
+        assert_eq!(
+            pool.try_push_back(a, 5).unwrap_err().error(),
+            Error::InvalidList
+        );
+
+        // The new list reuses the slot of `a`, but `a` must not reach it.
+        let c = pool.create_list();
+        let c1 = pool.push_back(c, 7);
+        assert_ne!(c, a);
+        assert!(!pool.contains_list(a));
+        assert_eq!(pool.list_of(c1), Some(c));
+        assert_eq!(pool.list_len(a), 0);
+        assert_eq!(pool.front_key(a), None);
+        assert_eq!(pool.try_move_to_front(c1, a), Err(Error::InvalidList));
+        assert_eq!(pool.remove_list(a), None);
+        assert_eq!(values(&pool, c), [7]);
+    }
+
+    // Run under Miri. An element of `b` sits between those of `a` in the slab, and must stay
+    // untouched while the walk over `a` hands out the values around it.
+    #[test]
+    fn iter_mut_references_coexist() {
+        let mut pool = ListPool::new();
+        let a = pool.create_list();
+        let b = pool.create_list();
+        pool.push_back(a, 1);
+        pool.push_back(b, 100);
+        pool.push_back(a, 2);
+        pool.push_front(a, 3);
+        let mut iter = pool.iter_mut(a);
+        let x = iter.next().unwrap();
+        let y = iter.next().unwrap();
+        let z = iter.next_back().unwrap();
+        *x += *y + *z;
+        assert_eq!(values(&pool, a), [6, 1, 2]);
+        assert_eq!(values(&pool, b), [100]);
+    }
+
+    #[test]
+    fn move_between_lists() {
+        let mut pool = ListPool::new();
+        let a = pool.create_list();
+        let b = pool.create_list();
+        let keys = (0..4).map(|v| pool.push_back(a, v)).collect::<Vec<_>>();
+
+        pool.move_to_back(keys[1], b);
+        pool.move_to_front(keys[3], b);
+        assert_eq!(values(&pool, a), [0, 2]);
+        assert_eq!(values(&pool, b), [3, 1]);
+        assert_eq!(pool.list_of(keys[3]), Some(b));
+        assert_eq!(pool.list_len(a), 2);
+        assert_eq!(pool.list_len(b), 2);
+
+        pool.move_after(keys[0], keys[1]);
+        pool.move_before(keys[2], keys[3]);
+        assert!(pool.list_is_empty(a));
+        assert_eq!(pool.front_key(a), None);
+        assert_eq!(pool.back_key(a), None);
+        assert_eq!(values(&pool, b), [2, 3, 1, 0]);
+        assert_eq!(
+            pool.keys(b).collect::<Vec<_>>(),
+            [2, 3, 1, 0].map(|i| keys[i])
+        );
+
+        pool.move_to_back(keys[2], b);
+        assert_eq!(values(&pool, b), [3, 1, 0, 2]);
+        assert_eq!(pool.try_move_before(keys[0], keys[0]), Err(Error::SameKey));
+        assert_eq!(
+            pool.try_move_to_back(
+                keys[0],
+                ListId {
+                    index: 9,
+                    generation: 0
+                }
+            ),
+            Err(Error::InvalidList)
+        );
+        assert_eq!(pool.try_move_to_back(99, b), Err(Error::InvalidKey));
+    }
+}
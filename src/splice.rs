@@ -0,0 +1,244 @@
+use crate::{Error, SlabLinkedList};
+
+// Elements moved between lists are reinserted into the destination slab, so they are
+// assigned new keys. These operations return `(old_key, new_key)` pairs in list order.
+impl<T> SlabLinkedList<T> {
+    /// Moves every element of `other` to the back of `self`, leaving `other` empty.
+    ///
+    /// If `self` is empty the elements keep their keys.
+    pub fn append(&mut self, other: &mut Self) -> Vec<(usize, usize)> {
+        if self.is_empty() {
+            std::mem::swap(self, other);
+            return self.keys().map(|key| (key, key)).collect();
+        }
+        let other = std::mem::take(other);
+        let mut old_keys = other.keys().collect::<Vec<_>>().into_iter();
+        let mut remap = Vec::with_capacity(other.len());
+        self.extend_back(other, |key| remap.push((old_keys.next().unwrap(), key)));
+        remap
+    }
+
+    /// Moves every element of `other` to the front of `self`, leaving `other` empty.
+    ///
+    /// If `self` is empty the elements keep their keys.
+    pub fn prepend(&mut self, other: &mut Self) -> Vec<(usize, usize)> {
+        if self.is_empty() {
+            return self.append(other);
+        }
+        let mut remap = Vec::with_capacity(other.len());
+        while let Some((old_key, value)) = other.pop_back_entry() {
+            remap.push((old_key, self.push_front(value)));
+        }
+        remap.reverse();
+        remap
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn splice_after(&mut self, target_key: usize, other: &mut Self) -> Vec<(usize, usize)> {
+        self.try_splice_after(target_key, other)
+            .expect("invalid key")
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn splice_before(&mut self, target_key: usize, other: &mut Self) -> Vec<(usize, usize)> {
+        self.try_splice_before(target_key, other)
+            .expect("invalid key")
+    }
+
+    /// Moves every element of `other` in after `target_key`, leaving `other` empty.
+    pub fn try_splice_after(
+        &mut self,
+        target_key: usize,
+        other: &mut Self,
+    ) -> Result<Vec<(usize, usize)>, Error> {
+        if !self.contains_key(target_key) {
+            return Err(Error::InvalidKey);
+        }
+        let mut remap = Vec::with_capacity(other.len());
+        while let Some((old_key, value)) = other.pop_back_entry() {
+            remap.push((old_key, self.insert_after(value, target_key)));
+        }
+        remap.reverse();
+        Ok(remap)
+    }
+
+    /// Moves every element of `other` in before `target_key`, leaving `other` empty.
+    pub fn try_splice_before(
+        &mut self,
+        target_key: usize,
+        other: &mut Self,
+    ) -> Result<Vec<(usize, usize)>, Error> {
+        if !self.contains_key(target_key) {
+            return Err(Error::InvalidKey);
+        }
+        let mut remap = Vec::with_capacity(other.len());
+        while let Some((old_key, value)) = other.pop_front_entry() {
+            remap.push((old_key, self.insert_before(value, target_key)));
+        }
+        Ok(remap)
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn split_off_after(&mut self, key: usize) -> (Self, Vec<(usize, usize)>) {
+        self.try_split_off_after(key).expect("invalid key")
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn split_off_before(&mut self, key: usize) -> (Self, Vec<(usize, usize)>) {
+        self.try_split_off_before(key).expect("invalid key")
+    }
+
+    /// Moves every element after `key` into a new list.
+    pub fn try_split_off_after(
+        &mut self,
+        key: usize,
+    ) -> Result<(Self, Vec<(usize, usize)>), Error> {
+        if !self.contains_key(key) {
+            return Err(Error::InvalidKey);
+        }
+        let mut other = Self::new();
+        let mut remap = Vec::new();
+        while self.tail != Some(key) {
+            let (old_key, value) = self.pop_back_entry().unwrap();
+            remap.push((old_key, other.push_front(value)));
+        }
+        remap.reverse();
+        Ok((other, remap))
+    }
+
+    /// Moves every element before `key` into a new list.
+    pub fn try_split_off_before(
+        &mut self,
+        key: usize,
+    ) -> Result<(Self, Vec<(usize, usize)>), Error> {
+        if !self.contains_key(key) {
+            return Err(Error::InvalidKey);
+        }
+        let mut other = Self::new();
+        let mut remap = Vec::new();
+        while self.head != Some(key) {
+            let (old_key, value) = self.pop_front_entry().unwrap();
+            remap.push((old_key, other.push_back(value)));
+        }
+        Ok((other, remap))
+    }
+
+    #[inline]
+    fn pop_front_entry(&mut self) -> Option<(usize, T)> {
+        let key = self.head?;
+        Some((key, self.remove(key)))
+    }
+
+    #[inline]
+    fn pop_back_entry(&mut self) -> Option<(usize, T)> {
+        let key = self.tail?;
+        Some((key, self.remove(key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values<T: Copy>(list: &SlabLinkedList<T>) -> Vec<T> {
+        list.iter().copied().collect()
+    }
+
+    fn check_remap<T: Copy + PartialEq + std::fmt::Debug>(
+        before: &[(usize, T)],
+        remap: &[(usize, usize)],
+        after: &SlabLinkedList<T>,
+    ) {
+        assert_eq!(before.len(), remap.len());
+        for (&(key, value), &(old_key, new_key)) in before.iter().zip(remap) {
+            assert_eq!(key, old_key);
+            assert_eq!(after[new_key], value);
+        }
+    }
+
+    fn entries<T: Copy>(list: &SlabLinkedList<T>) -> Vec<(usize, T)> {
+        list.iter_with_keys()
+            .map(|(key, value)| (key, *value))
+            .collect()
+    }
+
+    #[test]
+    fn append_and_prepend() {
+        let mut list = SlabLinkedList::from([1, 2]);
+        let mut other = SlabLinkedList::from([3, 4]);
+        other.push_front(0);
+        other.pop_front();
+        let before = entries(&other);
+        let remap = list.append(&mut other);
+        check_remap(&before, &remap, &list);
+        assert!(other.is_empty());
+        assert_eq!(values(&list), [1, 2, 3, 4]);
+
+        let mut other = SlabLinkedList::from([-1, 0]);
+        let before = entries(&other);
+        let remap = list.prepend(&mut other);
+        check_remap(&before, &remap, &list);
+        assert!(other.is_empty());
+        assert_eq!(values(&list), [-1, 0, 1, 2, 3, 4]);
+        assert_eq!(list.validate(), Ok(()));
+
+        let mut empty = SlabLinkedList::new();
+        let remap = empty.prepend(&mut list);
+        assert!(remap.iter().all(|(old, new)| old == new));
+        assert_eq!(values(&empty), [-1, 0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn splice() {
+        let mut list = SlabLinkedList::from([1, 4]);
+        let k1 = list.front_key().unwrap();
+        let k4 = list.back_key().unwrap();
+
+        let mut other = SlabLinkedList::from([2, 3]);
+        let before = entries(&other);
+        let remap = list.splice_after(k1, &mut other);
+        check_remap(&before, &remap, &list);
+
+        let mut other = SlabLinkedList::from([5, 6]);
+        list.splice_after(k4, &mut other);
+        let mut other = SlabLinkedList::from([0]);
+        let before = entries(&other);
+        let remap = list.splice_before(k1, &mut other);
+        check_remap(&before, &remap, &list);
+        assert_eq!(values(&list), [0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(list.validate(), Ok(()));
+
+        let mut other = SlabLinkedList::from([7]);
+        assert_eq!(
+            list.try_splice_after(100, &mut other),
+            Err(Error::InvalidKey)
+        );
+        assert_eq!(other.len(), 1);
+    }
+
+    #[test]
+    fn split_off() {
+        let mut list = SlabLinkedList::from([0, 1, 2, 3, 4, 5]);
+        let keys = list.keys().collect::<Vec<_>>();
+        let before = entries(&list);
+
+        let (after, remap) = list.split_off_after(keys[3]);
+        assert_eq!(values(&after), [4, 5]);
+        check_remap(&before[4..], &remap, &after);
+
+        let (front, remap) = list.split_off_before(keys[1]);
+        assert_eq!(values(&front), [0]);
+        check_remap(&before[..1], &remap, &front);
+        assert_eq!(values(&list), [1, 2, 3]);
+        assert_eq!(list.keys().collect::<Vec<_>>(), keys[1..4]);
+        assert_eq!(list.validate(), Ok(()));
+
+        let (rest, remap) = list.split_off_after(keys[3]);
+        assert!(rest.is_empty() && remap.is_empty());
+        assert!(list.try_split_off_before(keys[0]).is_err());
+    }
+}
@@ -0,0 +1,423 @@
+use crate::generational::Generations;
+use crate::{ListId, ListPool};
+use std::iter::FusedIterator;
+
+const BITS: usize = 6;
+const SLOTS: usize = 1 << BITS;
+// Enough levels for every `u64` deadline.
+const LEVELS: usize = 64_usize.div_ceil(BITS);
+const EXPIRED: usize = usize::MAX;
+
+/// Identifies a scheduled timer. Once the timer has fired or been cancelled, its key no longer
+/// refers to anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimerKey {
+    index: usize,
+    generation: u32,
+}
+
+/// A hierarchical timing wheel driven by a caller-supplied tick counter.
+///
+/// Level `n` has 64 slots of `64^n` ticks each, and every slot is a list in one shared
+/// [`ListPool`]. Timers are moved between slots as the wheel advances but keep their key,
+/// so cancelling is O(1).
+#[derive(Debug, Clone)]
+pub struct TimerWheel<T> {
+    pool: ListPool<Timer<T>>,
+    slots: Vec<ListId>,
+    occupied: [u64; LEVELS],
+    expired: ListId,
+    elapsed: u64,
+    generations: Generations,
+}
+
+#[derive(Debug, Clone)]
+struct Timer<T> {
+    value: T,
+    deadline: u64,
+    slot: usize,
+}
+
+impl<T> Default for TimerWheel<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> TimerWheel<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut pool = ListPool::with_capacity(capacity);
+        let slots = (0..LEVELS * SLOTS).map(|_| pool.create_list()).collect();
+        let expired = pool.create_list();
+        Self {
+            pool,
+            slots,
+            occupied: [0; LEVELS],
+            expired,
+            elapsed: 0,
+            generations: Generations::with_capacity(capacity),
+        }
+    }
+
+    /// The tick the wheel has advanced to.
+    #[inline]
+    pub fn now(&self) -> u64 {
+        self.elapsed
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pool.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pool.is_empty()
+    }
+
+    #[inline]
+    fn check(&self, key: TimerKey) -> Option<usize> {
+        let generation = self.generations.get(key.index);
+        (generation == key.generation && self.pool.contains_key(key.index)).then_some(key.index)
+    }
+
+    #[inline]
+    fn issue(&mut self, index: usize) -> TimerKey {
+        TimerKey {
+            index,
+            generation: self.generations.issue(index),
+        }
+    }
+
+    #[inline]
+    fn retire(&mut self, index: usize) -> Timer<T> {
+        self.generations.retire(index);
+        self.pool.remove(index)
+    }
+
+    #[inline]
+    pub fn contains(&self, key: TimerKey) -> bool {
+        self.check(key).is_some()
+    }
+
+    #[inline]
+    pub fn get(&self, key: TimerKey) -> Option<&T> {
+        self.pool.get(self.check(key)?).map(|timer| &timer.value)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, key: TimerKey) -> Option<&mut T> {
+        let index = self.check(key)?;
+        self.pool.get_mut(index).map(|timer| &mut timer.value)
+    }
+
+    #[inline]
+    pub fn deadline(&self, key: TimerKey) -> Option<u64> {
+        self.pool.get(self.check(key)?).map(|timer| timer.deadline)
+    }
+
+    /// Schedules `value` to fire once the wheel reaches `deadline`. A deadline that is not
+    /// after [`now`](Self::now) fires on the next [`advance`](Self::advance).
+    pub fn schedule(&mut self, deadline: u64, value: T) -> TimerKey {
+        let slot = self.slot_for(deadline);
+        let timer = Timer {
+            value,
+            deadline,
+            slot,
+        };
+        let key = match slot {
+            EXPIRED => self.push_expired(timer),
+            _ => self.pool.push_back(self.slots[slot], timer),
+        };
+        self.occupy(slot);
+        self.issue(key)
+    }
+
+    // Keeps the expired list in deadline order, with timers due at the same tick in the order
+    // they were scheduled. Late timers are usually due close to now, so the search starts at
+    // the back.
+    fn push_expired(&mut self, timer: Timer<T>) -> usize {
+        let mut target = self.pool.back_key(self.expired);
+        while let Some(key) = target {
+            if self.pool[key].deadline <= timer.deadline {
+                break;
+            }
+            target = self.pool.prev_key(key);
+        }
+        match target {
+            Some(key) => self.pool.insert_after(timer, key),
+            None => self.pool.push_front(self.expired, timer),
+        }
+    }
+
+    pub fn cancel(&mut self, key: TimerKey) -> Option<T> {
+        let timer = self.retire(self.check(key)?);
+        if timer.slot != EXPIRED && self.pool.list_is_empty(self.slots[timer.slot]) {
+            self.occupied[timer.slot / SLOTS] &= !(1 << (timer.slot % SLOTS));
+        }
+        Some(timer.value)
+    }
+
+    /// Returns the earliest tick at which [`advance`](Self::advance) has work to do.
+    ///
+    /// This is exact for timers due within 64 ticks and a lower bound otherwise, since timers
+    /// on higher levels are only sorted into finer slots when that tick is reached.
+    pub fn next_expiration(&self) -> Option<u64> {
+        if !self.pool.list_is_empty(self.expired) {
+            return Some(self.elapsed);
+        }
+        self.next_slot().map(|(_, tick)| tick)
+    }
+
+    /// Advances the wheel to `now`, cascading timers down the levels, and returns the timers
+    /// whose deadline has been reached in deadline order.
+    ///
+    /// Timers not consumed from the returned iterator stay expired and are returned first by the
+    /// next call.
+    #[must_use = "expired timers are only removed by iterating"]
+    pub fn advance(&mut self, now: u64) -> Expired<'_, T> {
+        while let Some((slot, tick)) = self.next_slot() {
+            if tick > now {
+                break;
+            }
+            self.elapsed = tick;
+            self.occupied[slot / SLOTS] &= !(1 << (slot % SLOTS));
+            let list = self.slots[slot];
+            while let Some(key) = self.pool.front_key(list) {
+                let slot = self.slot_for(self.pool[key].deadline);
+                self.pool.move_to_back(key, self.list(slot));
+                self.pool[key].slot = slot;
+                self.occupy(slot);
+            }
+        }
+        self.elapsed = self.elapsed.max(now);
+        Expired { wheel: self }
+    }
+
+    pub fn clear(&mut self) {
+        for slot in (0..self.slots.len()).chain([EXPIRED]) {
+            let list = self.list(slot);
+            while let Some(index) = self.pool.front_key(list) {
+                self.retire(index);
+            }
+        }
+        self.occupied = [0; LEVELS];
+    }
+
+    #[inline]
+    fn slot_for(&self, deadline: u64) -> usize {
+        if deadline <= self.elapsed {
+            return EXPIRED;
+        }
+        // The highest bit in which the deadline differs from now picks the level.
+        let masked = (self.elapsed ^ deadline) | (SLOTS as u64 - 1);
+        let level = (63 - masked.leading_zeros() as usize) / BITS;
+        let slot = (deadline >> (level * BITS)) as usize % SLOTS;
+        level * SLOTS + slot
+    }
+
+    #[inline]
+    fn list(&self, slot: usize) -> ListId {
+        match slot {
+            EXPIRED => self.expired,
+            _ => self.slots[slot],
+        }
+    }
+
+    #[inline]
+    fn occupy(&mut self, slot: usize) {
+        if slot != EXPIRED {
+            self.occupied[slot / SLOTS] |= 1 << (slot % SLOTS);
+        }
+    }
+
+    // Timers on a level are always due before those on any higher level, so the first
+    // occupied slot of the lowest occupied level is next.
+    fn next_slot(&self) -> Option<(usize, u64)> {
+        (0..LEVELS).find_map(|level| {
+            let occupied = self.occupied[level];
+            if occupied == 0 {
+                return None;
+            }
+            let shift = level * BITS;
+            let now_slot = (self.elapsed >> shift) as u32 % SLOTS as u32;
+            let slot = (now_slot + occupied.rotate_right(now_slot).trailing_zeros()) % SLOTS as u32;
+            let level_start = match shift + BITS {
+                64.. => 0,
+                bits => self.elapsed & !((1 << bits) - 1),
+            };
+            Some((
+                level * SLOTS + slot as usize,
+                level_start + (u64::from(slot) << shift),
+            ))
+        })
+    }
+}
+
+/// Timers that fired during [`TimerWheel::advance`].
+#[derive(Debug)]
+pub struct Expired<'a, T> {
+    wheel: &'a mut TimerWheel<T>,
+}
+
+impl<T> Iterator for Expired<'_, T> {
+    type Item = (TimerKey, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.wheel.pool.front_key(self.wheel.expired)?;
+        let key = self.wheel.issue(index);
+        Some((key, self.wheel.retire(index).value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.wheel.pool.list_len(self.wheel.expired);
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Expired<'_, T> {}
+
+impl<T> FusedIterator for Expired<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::Rng;
+
+    #[test]
+    fn fires_in_deadline_order() {
+        let mut wheel = TimerWheel::new();
+        let deadlines = [5, 1, 64, 63, 4099, 100, 1 << 40, u64::MAX, 100];
+        let keys = deadlines.map(|deadline| wheel.schedule(deadline, deadline));
+        assert_eq!(wheel.len(), deadlines.len());
+        assert_eq!(wheel.next_expiration(), Some(1));
+        assert_eq!(wheel.deadline(keys[4]), Some(4099));
+
+        assert!(wheel.advance(0).next().is_none());
+        assert_eq!(wheel.advance(5).map(|(_, v)| v).collect::<Vec<_>>(), [1, 5]);
+        assert_eq!(
+            wheel.advance(99).map(|(_, v)| v).collect::<Vec<_>>(),
+            [63, 64]
+        );
+        assert_eq!(
+            wheel.advance(100).collect::<Vec<_>>(),
+            [(keys[5], 100), (keys[8], 100)]
+        );
+        assert_eq!(wheel.cancel(keys[4]), Some(4099));
+        assert_eq!(wheel.cancel(keys[4]), None);
+        assert!(wheel.advance(1 << 39).next().is_none());
+        assert_eq!(wheel.now(), 1 << 39);
+        assert_eq!(
+            wheel.advance(1 << 40).map(|(_, v)| v).collect::<Vec<_>>(),
+            [1 << 40]
+        );
+        assert_eq!(
+            wheel.advance(u64::MAX).map(|(_, v)| v).collect::<Vec<_>>(),
+            [u64::MAX]
+        );
+        assert!(wheel.is_empty());
+        assert_eq!(wheel.next_expiration(), None);
+    }
+
+    #[test]
+    fn past_deadlines_and_leftovers() {
+        let mut wheel = TimerWheel::new();
+        assert!(wheel.advance(1000).next().is_none());
+        let key = wheel.schedule(10, "late");
+        wheel.schedule(1000, "now");
+        wheel.schedule(1001, "soon");
+        assert_eq!(wheel.next_expiration(), Some(1000));
+        assert_eq!(wheel.get(key), Some(&"late"));
+        *wheel.get_mut(key).unwrap() = "very late";
+
+        let mut expired = wheel.advance(1000);
+        assert_eq!(expired.len(), 2);
+        assert_eq!(expired.next(), Some((key, "very late")));
+        assert_eq!(wheel.len(), 2);
+        assert_eq!(wheel.next_expiration(), Some(1000));
+        assert_eq!(
+            wheel.advance(1001).map(|(_, v)| v).collect::<Vec<_>>(),
+            ["now", "soon"]
+        );
+
+        // Late timers are merged into the expired list by deadline, ahead of a leftover that is
+        // due later.
+        wheel.schedule(1004, "first");
+        wheel.schedule(1005, "leftover");
+        assert_eq!(wheel.advance(1005).next().map(|(_, v)| v), Some("first"));
+        wheel.schedule(1003, "a");
+        wheel.schedule(1001, "b");
+        wheel.schedule(1003, "c");
+        wheel.schedule(1002, "d");
+        assert_eq!(
+            wheel.advance(1005).map(|(_, v)| v).collect::<Vec<_>>(),
+            ["b", "d", "a", "c", "leftover"]
+        );
+        wheel.schedule(1500, "later");
+        wheel.clear();
+        assert!(wheel.advance(2000).next().is_none());
+    }
+
+    #[test]
+    fn stale_keys() {
+        let mut wheel = TimerWheel::new();
+        let fired = wheel.schedule(5, "a");
+        assert_eq!(wheel.advance(5).next(), Some((fired, "a")));
+        let key = wheel.schedule(10, "b");
+        assert_eq!(key.index, fired.index);
+        assert_ne!(key, fired);
+        assert_eq!(wheel.cancel(fired), None);
+        assert!(!wheel.contains(fired));
+        assert_eq!(wheel.get(fired), None);
+        assert_eq!(wheel.get_mut(fired), None);
+        assert_eq!(wheel.deadline(fired), None);
+        assert_eq!(wheel.get(key), Some(&"b"));
+
+        assert_eq!(wheel.cancel(key), Some("b"));
+        let key2 = wheel.schedule(20, "c");
+        assert_eq!(wheel.cancel(key), None);
+        wheel.clear();
+        assert_eq!(wheel.cancel(key2), None);
+        let key3 = wheel.schedule(30, "d");
+        assert_eq!(wheel.cancel(key2), None);
+        assert_eq!(wheel.cancel(key3), Some("d"));
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = Rng::new(0x2545_f491_4f6c_dd1d);
+        let mut rand = || rng.next_u64();
+        let mut wheel = TimerWheel::new();
+        let mut pending = Vec::new();
+        for _ in 0..200 {
+            for _ in 0..rand() % 8 {
+                let deadline = wheel.now() + rand() % (1 << (rand() % 20));
+                let key = wheel.schedule(deadline, deadline);
+                pending.push((key, deadline));
+            }
+            if rand() % 4 == 0 && !pending.is_empty() {
+                let (key, deadline) = pending.swap_remove(rand() as usize % pending.len());
+                assert_eq!(wheel.cancel(key), Some(deadline));
+            }
+            let now = wheel.now() + rand() % 5000;
+            let fired = wheel.advance(now).collect::<Vec<_>>();
+            assert!(fired.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+            let mut expected = pending.clone();
+            expected.retain(|&(_, deadline)| deadline <= now);
+            pending.retain(|&(_, deadline)| deadline > now);
+            expected.sort();
+            let mut fired = fired;
+            fired.sort();
+            assert_eq!(fired, expected);
+        }
+        assert_eq!(wheel.len(), pending.len());
+    }
+}